@@ -0,0 +1,33 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use perlin_noise::Perlin;
+
+const WIDTH: usize = 256;
+const HEIGHT: usize = 256;
+const STEP: f32 = 1. / 32.;
+
+fn per_point(noise: &Perlin, out: &mut [f32]) {
+    for (y, row) in out.chunks_exact_mut(WIDTH).enumerate() {
+        for (x, value) in row.iter_mut().enumerate() {
+            *value = noise.sample([x as f32 * STEP, y as f32 * STEP]);
+        }
+    }
+}
+
+fn grid_sampling(c: &mut Criterion) {
+    let noise = Perlin::new(0);
+    let mut out = vec![0.; WIDTH * HEIGHT];
+
+    let mut group = c.benchmark_group("perlin 256x256");
+    group.bench_function("per point", |b| {
+        b.iter(|| per_point(black_box(&noise), black_box(&mut out)))
+    });
+    group.bench_function("fill_grid", |b| {
+        b.iter(|| black_box(&noise).fill_grid([0., 0.], STEP, WIDTH, HEIGHT, black_box(&mut out)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, grid_sampling);
+criterion_main!(benches);
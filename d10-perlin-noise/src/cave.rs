@@ -0,0 +1,369 @@
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::fmt;
+
+use crate::{Fbm, FractalKind, Heightmap, Perlin};
+
+/// Cell of a [`CaveMap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Empty,
+    Solid,
+}
+
+/// Cave generator, thresholding a blend of plain and ridged Perlin fractals
+/// into rock and smoothing the result with a cellular automaton.
+#[derive(Clone, Debug)]
+pub struct Cave {
+    pub width: usize,
+    pub height: usize,
+    /// Noise lattice cells per tile, lower values give wider caverns.
+    pub frequency: f32,
+    pub octaves: u32,
+    /// Weight of the ridged fractal blended over the plain one, in `[0, 1]`.
+    /// Ridges carve long winding tunnels.
+    pub ridges: f32,
+    /// Blended noise at or above this level becomes rock.
+    pub threshold: f32,
+    /// Cellular automaton passes rounding off single tiles and thin walls.
+    pub smoothing: u32,
+}
+
+impl Cave {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            frequency: 0.08,
+            octaves: 3,
+            ridges: 0.3,
+            threshold: 0.1,
+            smoothing: 4,
+        }
+    }
+
+    /// Generates a cave where `start` and `end` are open and connected,
+    /// tunnelling through as little rock as possible when the noise leaves
+    /// them apart. Open tiles that cannot be reached from `start` are filled,
+    /// and the outer border is rock everywhere but at the two points.
+    ///
+    /// Points are `[x, y]` tiles with `y` growing downwards.
+    ///
+    /// # Panics
+    ///
+    /// If `start` or `end` lies outside the map.
+    pub fn generate(&self, seed: u64, start: [usize; 2], end: [usize; 2]) -> CaveMap {
+        let (width, height) = (self.width, self.height);
+        for [x, y] in [start, end] {
+            assert!(x < width && y < height, "cave point outside the map");
+        }
+
+        let fractal = |kind| Fbm {
+            octaves: self.octaves,
+            frequency: self.frequency,
+            kind,
+            ..Fbm::new(Perlin::new(seed))
+        };
+        let mut density =
+            Heightmap::from_noise(&fractal(FractalKind::Standard), [0.; 2], 1., width, height);
+        density.blend(
+            &Heightmap::from_noise(&fractal(FractalKind::Ridged), [0.; 2], 1., width, height),
+            self.ridges,
+        );
+
+        let mut map = CaveMap {
+            width,
+            height,
+            tiles: density
+                .threshold(self.threshold)
+                .into_iter()
+                .map(|solid| if solid { Tile::Solid } else { Tile::Empty })
+                .collect(),
+        };
+
+        for _ in 0..self.smoothing {
+            map.smooth();
+        }
+        for y in 0..height {
+            for x in 0..width {
+                if x == 0 || y == 0 || x + 1 == width || y + 1 == height {
+                    map.tiles[y * width + x] = Tile::Solid;
+                }
+            }
+        }
+
+        map.tunnel(start, end);
+
+        let reachable = map.reachable(start);
+        for (tile, reachable) in map.tiles.iter_mut().zip(reachable) {
+            if !reachable {
+                *tile = Tile::Solid;
+            }
+        }
+
+        map
+    }
+}
+
+/// Row-major tile grid made by [`Cave::generate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CaveMap {
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+}
+
+impl CaveMap {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// Tile at `[x, y]`, `None` outside the map.
+    pub fn get(&self, [x, y]: [usize; 2]) -> Option<Tile> {
+        (x < self.width && y < self.height).then(|| self.tiles[y * self.width + x])
+    }
+
+    /// Open tiles connected to `from` through their four direct neighbours.
+    pub fn reachable(&self, from: [usize; 2]) -> Vec<bool> {
+        let mut reached = vec![false; self.tiles.len()];
+        let mut stack = vec![from];
+
+        while let Some(point) = stack.pop() {
+            let index = point[1] * self.width + point[0];
+            if reached[index] || self.tiles[index] == Tile::Solid {
+                continue;
+            }
+            reached[index] = true;
+            stack.extend(self.neighbours(point));
+        }
+
+        reached
+    }
+
+    /// Horizontal runs of rock as `(x, y, length, 0)` platforms for the
+    /// terminal platformer, which draws them at row `rows - y` so the bottom
+    /// row of the map is its floor at `y = 1`.
+    ///
+    /// # Panics
+    ///
+    /// If the map is wider or taller than `u16::MAX` tiles.
+    pub fn platforms(&self) -> Vec<(u16, u16, u16, u16)> {
+        assert!(
+            self.width <= u16::MAX as usize && self.height <= u16::MAX as usize,
+            "platform coordinates must fit in u16"
+        );
+        let mut platforms = vec![];
+
+        for (row, tiles) in self.tiles.chunks_exact(self.width.max(1)).enumerate() {
+            let y = (self.height - row) as u16;
+            let mut x = 0;
+            while x < tiles.len() {
+                let length = tiles[x..]
+                    .iter()
+                    .take_while(|&&tile| tile == Tile::Solid)
+                    .count();
+                if length > 0 {
+                    platforms.push((x as u16, y, length as u16, 0));
+                }
+                x += length.max(1);
+            }
+        }
+
+        platforms
+    }
+
+    /// One step of the 4-5 rule: rock with at least five rock neighbours and
+    /// open tiles with at most three stay as they are, the rest flips.
+    /// Tiles outside the map count as rock.
+    fn smooth(&mut self) {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let solid = |x: isize, y: isize| {
+            !(0..width).contains(&x)
+                || !(0..height).contains(&y)
+                || self.tiles[(y * width + x) as usize] == Tile::Solid
+        };
+
+        let tiles = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let neighbours = (-1..=1)
+                    .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                    .filter(|&(dx, dy)| (dx, dy) != (0, 0) && solid(x + dx, y + dy))
+                    .count();
+                match neighbours {
+                    5.. => Tile::Solid,
+                    0..=3 => Tile::Empty,
+                    _ => self.tiles[(y * width + x) as usize],
+                }
+            })
+            .collect();
+
+        self.tiles = tiles;
+    }
+
+    /// Opens the cheapest path from `start` to `end`, counting every rock
+    /// tile dug through, with a 0-1 breadth first search.
+    fn tunnel(&mut self, start: [usize; 2], end: [usize; 2]) {
+        let index = |[x, y]: [usize; 2]| y * self.width + x;
+        let cost = |tile| if tile == Tile::Solid { 1 } else { 0 };
+
+        let mut distance = vec![usize::MAX; self.tiles.len()];
+        let mut previous = vec![None; self.tiles.len()];
+        let mut queue = VecDeque::from([start]);
+        distance[index(start)] = cost(self.tiles[index(start)]);
+
+        while let Some(point) = queue.pop_front() {
+            if point == end {
+                break;
+            }
+            for next in self.neighbours(point) {
+                let step = cost(self.tiles[index(next)]);
+                let through = distance[index(point)] + step;
+                if through < distance[index(next)] {
+                    distance[index(next)] = through;
+                    previous[index(next)] = Some(point);
+                    if step == 0 {
+                        queue.push_front(next);
+                    } else {
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        let mut point = Some(end);
+        while let Some(current) = point {
+            self.tiles[index(current)] = Tile::Empty;
+            point = previous[index(current)];
+        }
+    }
+
+    fn neighbours(&self, [x, y]: [usize; 2]) -> impl Iterator<Item = [usize; 2]> {
+        let (width, height) = (self.width, self.height);
+        [
+            (x > 0).then(|| [x - 1, y]),
+            (x + 1 < width).then(|| [x + 1, y]),
+            (y > 0).then(|| [x, y - 1]),
+            (y + 1 < height).then(|| [x, y + 1]),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// Draws rock as `#` and open tiles as spaces, one line per row.
+impl fmt::Display for CaveMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.tiles.chunks_exact(self.width.max(1)) {
+            for &tile in row {
+                f.write_str(if tile == Tile::Solid { "#" } else { " " })?;
+            }
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    const START: [usize; 2] = [2, 2];
+    const END: [usize; 2] = [77, 37];
+
+    fn cave(seed: u64) -> CaveMap {
+        Cave::new(80, 40).generate(seed, START, END)
+    }
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(cave(1), cave(1));
+        assert_ne!(cave(1), cave(2));
+    }
+
+    #[test]
+    fn connected_without_pockets() {
+        for seed in 0..8 {
+            let map = cave(seed);
+            let reachable = map.reachable(START);
+            assert!(reachable[END[1] * map.width() + END[0]]);
+
+            for (tile, reachable) in map.tiles().iter().zip(reachable) {
+                assert_eq!(*tile == Tile::Empty, reachable);
+            }
+        }
+    }
+
+    #[test]
+    fn solid_border() {
+        let map = cave(3);
+        for x in 0..map.width() {
+            assert_eq!(map.get([x, 0]), Some(Tile::Solid));
+            assert_eq!(map.get([x, map.height() - 1]), Some(Tile::Solid));
+        }
+        assert_eq!(map.get([map.width(), 0]), None);
+    }
+
+    #[test]
+    fn tunnels_through_solid_rock() {
+        let map = Cave {
+            threshold: -2.,
+            ..Cave::new(12, 6)
+        }
+        .generate(0, [1, 1], [10, 4]);
+        let open = map
+            .tiles()
+            .iter()
+            .filter(|&&tile| tile == Tile::Empty)
+            .count();
+        // the shortest path between the points
+        assert_eq!(open, 9 + 3 + 1);
+    }
+
+    #[test]
+    fn platforms_cover_rock() {
+        let map = cave(4);
+        let platforms = map.platforms();
+        let covered: usize = platforms
+            .iter()
+            .map(|&(_, _, length, _)| length as usize)
+            .sum();
+        let solid = map
+            .tiles()
+            .iter()
+            .filter(|&&tile| tile == Tile::Solid)
+            .count();
+        assert_eq!(covered, solid);
+
+        // the bottom border row is one platform on the floor
+        assert!(platforms.contains(&(0, 1, 80, 0)));
+        assert!(platforms.iter().all(|&(_, y, _, _)| (1..=40).contains(&y)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn platforms_past_u16() {
+        let map = CaveMap {
+            width: u16::MAX as usize + 1,
+            height: 1,
+            tiles: vec![Tile::Solid; u16::MAX as usize + 1],
+        };
+        map.platforms();
+    }
+
+    #[test]
+    fn display_rows() {
+        let text = cave(5).to_string();
+        assert_eq!(text.lines().count(), 40);
+        assert!(text.lines().all(|line| line.len() == 80));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::Float;
+use crate::{lerp, Perlin};
+
+impl Perlin {
+    /// Samples the noise like [`Perlin::sample`], also returning the
+    /// analytic partial derivatives `[d/dx, d/dy]` of the field at `point`.
+    pub fn sample_with_derivative(&self, point @ [px, py]: [f32; 2]) -> (f32, [f32; 2]) {
+        let (gx1, gy1) = (Float::floor(px) as i32, Float::floor(py) as i32);
+        let (gx2, gy2) = (gx1 + 1, gy1 + 1);
+
+        let (x_offset, y_offset) = (px - gx1 as f32, py - gy1 as f32);
+        let interpolation = self.interpolation();
+        let (x_weight, y_weight) = (interpolation.fade(x_offset), interpolation.fade(y_offset));
+        let (dx_weight, dy_weight) = (
+            interpolation.derivative(x_offset),
+            interpolation.derivative(y_offset),
+        );
+
+        // grid bottom left, bottom right, top left, top right, each corner's
+        // value is linear in the point so its gradient is its own slope
+        let (gbl, dbl) = (
+            self.grid_point_gradient([gx1, gy1], point),
+            self.gradient([gx1, gy1]),
+        );
+        let (gbr, dbr) = (
+            self.grid_point_gradient([gx2, gy1], point),
+            self.gradient([gx2, gy1]),
+        );
+        let (gtl, dtl) = (
+            self.grid_point_gradient([gx1, gy2], point),
+            self.gradient([gx1, gy2]),
+        );
+        let (gtr, dtr) = (
+            self.grid_point_gradient([gx2, gy2], point),
+            self.gradient([gx2, gy2]),
+        );
+
+        let lower = lerp(gbl, gbr, x_weight);
+        let upper = lerp(gtl, gtr, x_weight);
+        let value = lerp(lower, upper, y_weight);
+
+        let lower_derivative = [
+            Float::mul_add(dx_weight, gbr - gbl, lerp(dbl[0], dbr[0], x_weight)),
+            lerp(dbl[1], dbr[1], x_weight),
+        ];
+        let upper_derivative = [
+            Float::mul_add(dx_weight, gtr - gtl, lerp(dtl[0], dtr[0], x_weight)),
+            lerp(dtl[1], dtr[1], x_weight),
+        ];
+
+        let derivative = [
+            lerp(lower_derivative[0], upper_derivative[0], y_weight),
+            Float::mul_add(
+                dy_weight,
+                upper - lower,
+                lerp(lower_derivative[1], upper_derivative[1], y_weight),
+            ),
+        ];
+
+        (value, derivative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_points, Interpolation};
+
+    fn central_difference(noise: &Perlin, [px, py]: [f32; 2]) -> [f32; 2] {
+        let h = 1e-3;
+        [
+            (noise.sample([px + h, py]) - noise.sample([px - h, py])) / (2. * h),
+            (noise.sample([px, py + h]) - noise.sample([px, py - h])) / (2. * h),
+        ]
+    }
+
+    #[test]
+    fn value_matches_sample() {
+        let noise = Perlin::new(6);
+        for point in test_points::random(21, 1024, 8.) {
+            let (value, _) = noise.sample_with_derivative(point);
+            assert!((value - noise.sample(point)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn matches_finite_differences() {
+        for interpolation in [
+            Interpolation::Linear,
+            Interpolation::Smoothstep,
+            Interpolation::Quintic,
+        ] {
+            let noise = Perlin::new(6).with_interpolation(interpolation);
+            for point in test_points::random(21, 1024, 8.) {
+                // linear interpolation has kinks on the cell boundaries
+                let fraction = point.map(|p| p - p.floor());
+                if fraction.iter().any(|&f| !(1e-2..=1. - 1e-2).contains(&f)) {
+                    continue;
+                }
+
+                let (_, [dx, dy]) = noise.sample_with_derivative(point);
+                let [fx, fy] = central_difference(&noise, point);
+                assert!((dx - fx).abs() < 1e-2, "{interpolation:?} {point:?}");
+                assert!((dy - fy).abs() < 1e-2, "{interpolation:?} {point:?}");
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn default_seed_convenience() {
+        use crate::{perlin, perlin_with_derivative};
+
+        let (value, _) = perlin_with_derivative([1.3, -0.4]);
+        assert_eq!(value, perlin([1.3, -0.4]));
+    }
+}
@@ -0,0 +1,266 @@
+use alloc::{vec, vec::Vec};
+
+use crate::Float;
+use crate::{blerp, rng::DefaultRng, Heightmap, NoiseRng};
+
+/// Diffusion limited aggregation, growing a branching cluster from the grid
+/// centre by letting random walkers stick to it, which makes mountain ridge
+/// like patterns.
+#[derive(Clone, Debug)]
+pub struct Dla {
+    pub width: usize,
+    pub height: usize,
+    /// Walkers added to the cluster, growth stops early once it reaches the
+    /// grid border.
+    pub particles: usize,
+}
+
+impl Dla {
+    /// Grid of `width` by `height` cells, filling about a tenth of them.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            particles: width * height / 10,
+        }
+    }
+
+    /// Grows the cluster with walkers driven by the crate's default generator
+    /// seeded with `seed`, returning row-major cells that are part of it.
+    pub fn grow(&self, seed: u64) -> Vec<bool> {
+        self.grow_with_rng::<DefaultRng>(seed)
+    }
+
+    /// Like [`Dla::grow`], driving the walkers with a seeded `R`.
+    pub fn grow_with_rng<R: NoiseRng>(&self, seed: u64) -> Vec<bool> {
+        let (width, height) = (self.width, self.height);
+        let mut cluster = vec![false; width * height];
+        if width == 0 || height == 0 {
+            return cluster;
+        }
+
+        let mut rng = R::with_seed(seed);
+        let centre = [width / 2, height / 2];
+        cluster[centre[1] * width + centre[0]] = true;
+
+        let distance = |[x, y]: [usize; 2]| {
+            Float::hypot(x as f32 - centre[0] as f32, y as f32 - centre[1] as f32)
+        };
+        let border = (width.min(height) / 2) as f32;
+        let mut radius = 0f32;
+
+        for _ in 1..self.particles {
+            if radius + 2. >= border {
+                break;
+            }
+
+            // walkers start just outside the cluster and restart when they
+            // wander too far from it, instead of crossing the whole grid
+            let spawn = radius + 2.;
+            let kill = (spawn * 2.).max(spawn + 8.);
+            let mut walker = spawn_on_circle(&mut rng, centre, spawn, width, height);
+
+            loop {
+                if touches(&cluster, width, height, walker) {
+                    cluster[walker[1] * width + walker[0]] = true;
+                    radius = radius.max(distance(walker));
+                    break;
+                }
+
+                let [x, y] = walker;
+                walker = match rng.index(3) {
+                    0 => [(x + 1).min(width - 1), y],
+                    1 => [x.saturating_sub(1), y],
+                    2 => [x, (y + 1).min(height - 1)],
+                    _ => [x, y.saturating_sub(1)],
+                };
+                if distance(walker) > kill {
+                    walker = spawn_on_circle(&mut rng, centre, spawn, width, height);
+                }
+            }
+        }
+
+        cluster
+    }
+
+    /// Turns a grown `cluster` into a smooth heightmap in `[-1, 1]`, ready to
+    /// be blended with noise.
+    ///
+    /// The grid is upscaled `scale` times with bilinear filtering, then
+    /// softened by three box blurs of `radius` cells, approximating a
+    /// gaussian. The result is `width * scale` by `height * scale`.
+    ///
+    /// # Panics
+    ///
+    /// If `cluster` does not hold exactly `width * height` cells.
+    pub fn heightmap(&self, cluster: &[bool], scale: usize, radius: usize) -> Heightmap {
+        let (width, height) = (self.width, self.height);
+        assert_eq!(
+            cluster.len(),
+            width * height,
+            "cluster must hold width * height cells"
+        );
+
+        let scale = scale.max(1);
+        let (out_width, out_height) = (width * scale, height * scale);
+        let cell = |x: usize, y: usize| if cluster[y * width + x] { 1. } else { 0. };
+
+        let mut heights = Vec::with_capacity(out_width * out_height);
+        for oy in 0..out_height {
+            // sample between cell centres so every cell keeps its position
+            let sy = ((oy as f32 + 0.5) / scale as f32 - 0.5).clamp(0., (height - 1) as f32);
+            let y1 = sy as usize;
+            let y_weight = sy - y1 as f32;
+            let y2 = (y1 + 1).min(height - 1);
+
+            for ox in 0..out_width {
+                let sx = ((ox as f32 + 0.5) / scale as f32 - 0.5).clamp(0., (width - 1) as f32);
+                let x1 = sx as usize;
+                let x_weight = sx - x1 as f32;
+                let x2 = (x1 + 1).min(width - 1);
+
+                heights.push(blerp(
+                    cell(x1, y2),
+                    cell(x2, y2),
+                    cell(x1, y1),
+                    cell(x2, y1),
+                    x_weight,
+                    y_weight,
+                ));
+            }
+        }
+
+        let mut scratch = vec![0.; heights.len()];
+        for _ in 0..3 {
+            box_blur(&heights, &mut scratch, out_width, out_height, radius, true);
+            box_blur(&scratch, &mut heights, out_width, out_height, radius, false);
+        }
+
+        let mut map = Heightmap::from_values(out_width, out_height, heights);
+        map.normalize(-1., 1.);
+        map
+    }
+}
+
+fn spawn_on_circle(
+    rng: &mut impl NoiseRng,
+    centre: [usize; 2],
+    radius: f32,
+    width: usize,
+    height: usize,
+) -> [usize; 2] {
+    let angle = rng.f32() * 2. * core::f32::consts::PI;
+    let x = Float::floor(centre[0] as f32 + radius * Float::cos(angle) + 0.5);
+    let y = Float::floor(centre[1] as f32 + radius * Float::sin(angle) + 0.5);
+    [
+        (x.max(0.) as usize).min(width - 1),
+        (y.max(0.) as usize).min(height - 1),
+    ]
+}
+
+/// Whether `cell` or one of its four direct neighbours is in the cluster.
+fn touches(cluster: &[bool], width: usize, height: usize, [x, y]: [usize; 2]) -> bool {
+    let at = |x: usize, y: usize| cluster[y * width + x];
+    at(x, y)
+        || (x > 0 && at(x - 1, y))
+        || (x + 1 < width && at(x + 1, y))
+        || (y > 0 && at(x, y - 1))
+        || (y + 1 < height && at(x, y + 1))
+}
+
+/// Averages every value with the `radius` values on either side along rows
+/// or columns, clamping at the borders.
+fn box_blur(
+    from: &[f32],
+    to: &mut [f32],
+    width: usize,
+    height: usize,
+    radius: usize,
+    horizontal: bool,
+) {
+    let span = (2 * radius + 1) as f32;
+    for y in 0..height {
+        for x in 0..width {
+            let (position, length) = if horizontal { (x, width) } else { (y, height) };
+            let sum: f32 = (0..=2 * radius)
+                .map(|offset| {
+                    let at = (position + offset).saturating_sub(radius).min(length - 1);
+                    if horizontal {
+                        from[y * width + at]
+                    } else {
+                        from[at * width + x]
+                    }
+                })
+                .sum();
+            to[y * width + x] = sum / span;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SplitMix64;
+
+    #[test]
+    fn deterministic() {
+        let dla = Dla::new(48, 32);
+        assert_eq!(dla.grow(3), dla.grow(3));
+        assert_ne!(dla.grow(3), dla.grow(4));
+        assert_eq!(
+            dla.grow_with_rng::<SplitMix64>(3),
+            dla.grow_with_rng::<SplitMix64>(3)
+        );
+    }
+
+    #[test]
+    fn cluster_connected() {
+        let dla = Dla::new(40, 40);
+        let cluster = dla.grow(1);
+        let count = cluster.iter().filter(|&&cell| cell).count();
+        assert!(count > 40);
+        assert!(count <= dla.particles);
+
+        // flood fill from the centre reaches every cell of the cluster
+        let mut reached = vec![false; cluster.len()];
+        let mut stack = vec![[20, 20]];
+        while let Some([x, y]) = stack.pop() {
+            if x >= 40 || y >= 40 || !cluster[y * 40 + x] || reached[y * 40 + x] {
+                continue;
+            }
+            let index = y * 40 + x;
+            reached[index] = true;
+            stack.extend([
+                [x + 1, y],
+                [x.wrapping_sub(1), y],
+                [x, y + 1],
+                [x, y.wrapping_sub(1)],
+            ]);
+        }
+        assert_eq!(reached, cluster);
+    }
+
+    #[test]
+    fn heightmap_range() {
+        let dla = Dla::new(24, 16);
+        let map = dla.heightmap(&dla.grow(2), 4, 2);
+        assert_eq!((map.width(), map.height()), (24 * 4, 16 * 4));
+        assert_eq!(map.min_max(), Some((-1., 1.)));
+    }
+
+    #[test]
+    fn heightmap_peaks_on_cluster() {
+        let dla = Dla::new(32, 32);
+        let cluster = dla.grow(5);
+        let heights = dla.heightmap(&cluster, 2, 1);
+
+        let mean = |on: bool| {
+            let cells: Vec<f32> = (0..32 * 32)
+                .filter(|&i| cluster[i] == on)
+                .map(|i| heights[[i % 32 * 2, i / 32 * 2]])
+                .collect();
+            cells.iter().sum::<f32>() / cells.len() as f32
+        };
+        assert!(mean(true) > mean(false));
+    }
+}
@@ -0,0 +1,336 @@
+use crate::Float;
+use crate::{rng::DefaultRng, NoiseRng};
+
+fn check_size(heights: &[f32], width: usize, height: usize) {
+    assert_eq!(
+        heights.len(),
+        width * height,
+        "heightmap must hold width * height values"
+    );
+}
+
+/// Particle based hydraulic erosion, rolling water droplets downhill that
+/// pick up sediment on steep slopes and drop it where they slow down.
+///
+/// Heights are row-major, one value per cell, in the same units as the noise
+/// they were sampled from.
+#[derive(Clone, Debug)]
+pub struct HydraulicErosion {
+    /// Number of droplets simulated, one after another.
+    pub droplets: u32,
+    /// Steps a droplet takes before it evaporates completely.
+    pub lifetime: u32,
+    /// How much of its previous direction a droplet keeps, in `[0, 1]`.
+    pub inertia: f32,
+    /// Sediment a droplet can carry per unit of slope, speed and water.
+    pub capacity: f32,
+    /// Slope used for the capacity on flat ground, so droplets keep eroding.
+    pub min_slope: f32,
+    /// Fraction of the free capacity picked up per step.
+    pub erosion_rate: f32,
+    /// Fraction of the excess sediment dropped per step.
+    pub deposition_rate: f32,
+    /// Fraction of the water lost per step.
+    pub evaporation_rate: f32,
+    pub gravity: f32,
+}
+
+impl HydraulicErosion {
+    pub fn new(droplets: u32) -> Self {
+        Self {
+            droplets,
+            lifetime: 30,
+            inertia: 0.05,
+            capacity: 4.,
+            min_slope: 0.01,
+            erosion_rate: 0.3,
+            deposition_rate: 0.3,
+            evaporation_rate: 0.01,
+            gravity: 4.,
+        }
+    }
+
+    /// Erodes `heights` in place with droplets dropped at positions picked
+    /// by the crate's default generator seeded with `seed`.
+    ///
+    /// # Panics
+    ///
+    /// If `heights` does not hold exactly `width * height` values.
+    pub fn erode(&self, heights: &mut [f32], width: usize, height: usize, seed: u64) {
+        self.erode_with_rng::<DefaultRng>(heights, width, height, seed)
+    }
+
+    /// Like [`HydraulicErosion::erode`], placing the droplets with a seeded `R`.
+    pub fn erode_with_rng<R: NoiseRng>(
+        &self,
+        heights: &mut [f32],
+        width: usize,
+        height: usize,
+        seed: u64,
+    ) {
+        check_size(heights, width, height);
+        if width < 2 || height < 2 {
+            return;
+        }
+
+        let mut rng = R::with_seed(seed);
+        let max = [(width - 1) as f32, (height - 1) as f32];
+
+        for _ in 0..self.droplets {
+            let mut position = [rng.f32() * max[0], rng.f32() * max[1]];
+            let mut direction = [0f32; 2];
+            let (mut speed, mut water, mut sediment) = (1f32, 1f32, 0f32);
+
+            for _ in 0..self.lifetime {
+                let (current, [gx, gy]) = sample(heights, width, position);
+
+                direction = [
+                    direction[0] * self.inertia - gx * (1. - self.inertia),
+                    direction[1] * self.inertia - gy * (1. - self.inertia),
+                ];
+                let length = Float::hypot(direction[0], direction[1]);
+                if length == 0. {
+                    break;
+                }
+                direction = direction.map(|d| d / length);
+
+                let next = [position[0] + direction[0], position[1] + direction[1]];
+                if !(0. ..max[0]).contains(&next[0]) || !(0. ..max[1]).contains(&next[1]) {
+                    break;
+                }
+
+                let delta = sample(heights, width, next).0 - current;
+                let capacity = (-delta).max(self.min_slope) * speed * water * self.capacity;
+
+                if delta > 0. || sediment > capacity {
+                    // fill the pit behind an uphill step, or shed what the
+                    // droplet can no longer carry
+                    let deposit = if delta > 0. {
+                        delta.min(sediment)
+                    } else {
+                        (sediment - capacity) * self.deposition_rate
+                    };
+                    sediment -= deposit;
+                    spread(heights, width, position, deposit);
+                } else {
+                    // never dig deeper than the step down, which would leave
+                    // a pit behind the droplet
+                    let erode = ((capacity - sediment) * self.erosion_rate).min(-delta);
+                    sediment += erode;
+                    spread(heights, width, position, -erode);
+                }
+
+                speed = Float::sqrt(Float::mul_add(speed, speed, -delta * self.gravity).max(0.));
+                water *= 1. - self.evaporation_rate;
+                position = next;
+            }
+        }
+    }
+}
+
+impl Default for HydraulicErosion {
+    fn default() -> Self {
+        Self::new(10_000)
+    }
+}
+
+/// Thermal (talus) erosion, sliding material down every slope steeper than
+/// the talus threshold until the terrain settles.
+///
+/// Mass is conserved and the result does not depend on any seed.
+#[derive(Clone, Debug)]
+pub struct ThermalErosion {
+    pub iterations: u32,
+    /// Largest height difference between neighbouring cells that stays put.
+    pub talus: f32,
+    /// Fraction of the excess slope moved per iteration, in `[0, 1]`.
+    pub rate: f32,
+}
+
+impl ThermalErosion {
+    pub fn new(iterations: u32) -> Self {
+        Self {
+            iterations,
+            talus: 0.01,
+            rate: 0.5,
+        }
+    }
+
+    /// Erodes `heights` in place, moving material to the four direct
+    /// neighbours of each cell.
+    ///
+    /// # Panics
+    ///
+    /// If `heights` does not hold exactly `width * height` values.
+    pub fn erode(&self, heights: &mut [f32], width: usize, height: usize) {
+        check_size(heights, width, height);
+        let mut change = alloc::vec![0f32; heights.len()];
+
+        for _ in 0..self.iterations {
+            change.fill(0.);
+
+            for y in 0..height {
+                for x in 0..width {
+                    let index = y * width + x;
+                    let neighbours = [
+                        (x > 0).then(|| index - 1),
+                        (x + 1 < width).then(|| index + 1),
+                        (y > 0).then(|| index - width),
+                        (y + 1 < height).then(|| index + width),
+                    ];
+                    let excess =
+                        |neighbour: usize| heights[index] - heights[neighbour] - self.talus;
+
+                    let (mut total, mut steepest) = (0f32, 0f32);
+                    for neighbour in neighbours.into_iter().flatten() {
+                        let excess = excess(neighbour);
+                        if excess > 0. {
+                            total += excess;
+                            steepest = steepest.max(excess);
+                        }
+                    }
+                    if total == 0. {
+                        continue;
+                    }
+
+                    // moving half the steepest excess levels that slope, so
+                    // no cell overshoots its neighbours
+                    let moved = steepest * 0.5 * self.rate;
+                    change[index] -= moved;
+                    for neighbour in neighbours.into_iter().flatten() {
+                        let excess = excess(neighbour);
+                        if excess > 0. {
+                            change[neighbour] += moved * excess / total;
+                        }
+                    }
+                }
+            }
+
+            for (height, change) in heights.iter_mut().zip(&change) {
+                *height += change;
+            }
+        }
+    }
+}
+
+impl Default for ThermalErosion {
+    fn default() -> Self {
+        Self::new(50)
+    }
+}
+
+/// Bilinear height and gradient at `position`, which must lie inside the
+/// last row and column.
+fn sample(heights: &[f32], width: usize, [px, py]: [f32; 2]) -> (f32, [f32; 2]) {
+    let (x, y) = (px as usize, py as usize);
+    let (u, v) = (px - x as f32, py - y as f32);
+    let index = y * width + x;
+    let (tl, tr) = (heights[index], heights[index + 1]);
+    let (bl, br) = (heights[index + width], heights[index + width + 1]);
+
+    let gradient = [
+        Float::mul_add(tr - tl, 1. - v, (br - bl) * v),
+        Float::mul_add(bl - tl, 1. - u, (br - tr) * u),
+    ];
+    let top = Float::mul_add(tr - tl, u, tl);
+    let bottom = Float::mul_add(br - bl, u, bl);
+
+    (Float::mul_add(bottom - top, v, top), gradient)
+}
+
+/// Adds `amount` to the four cells around `position`, weighted by how close
+/// each one is.
+fn spread(heights: &mut [f32], width: usize, [px, py]: [f32; 2], amount: f32) {
+    let (x, y) = (px as usize, py as usize);
+    let (u, v) = (px - x as f32, py - y as f32);
+    let index = y * width + x;
+
+    heights[index] += amount * (1. - u) * (1. - v);
+    heights[index + 1] += amount * u * (1. - v);
+    heights[index + width] += amount * (1. - u) * v;
+    heights[index + width + 1] += amount * u * v;
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::{Perlin, SplitMix64};
+
+    const SIZE: usize = 64;
+
+    fn terrain(seed: u64) -> Vec<f32> {
+        let mut heights = alloc::vec![0.; SIZE * SIZE];
+        Perlin::new(seed).fill_grid([0.; 2], 0.05, SIZE, SIZE, &mut heights);
+        heights
+    }
+
+    fn steepest(heights: &[f32]) -> f32 {
+        let rows = heights.chunks_exact(SIZE);
+        let across = rows.flat_map(|row| row.windows(2).map(|pair| (pair[1] - pair[0]).abs()));
+        let down = heights[SIZE..]
+            .iter()
+            .zip(heights)
+            .map(|(below, above)| (below - above).abs());
+        across.chain(down).fold(0., f32::max)
+    }
+
+    #[test]
+    fn hydraulic_deterministic() {
+        let erosion = HydraulicErosion::new(500);
+        let (mut a, mut b, mut c) = (terrain(1), terrain(1), terrain(1));
+        erosion.erode(&mut a, SIZE, SIZE, 7);
+        erosion.erode(&mut b, SIZE, SIZE, 7);
+        erosion.erode(&mut c, SIZE, SIZE, 8);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a, terrain(1));
+    }
+
+    #[test]
+    fn hydraulic_removes_material() {
+        let original = terrain(2);
+        let mut heights = original.clone();
+        HydraulicErosion::new(2000).erode_with_rng::<SplitMix64>(&mut heights, SIZE, SIZE, 3);
+
+        assert!(heights.iter().all(|height| height.is_finite()));
+        // sediment still carried when a droplet dies is lost
+        let sum = |heights: &[f32]| heights.iter().sum::<f32>();
+        assert!(sum(&heights) <= sum(&original) + 1e-3);
+    }
+
+    #[test]
+    fn thermal_conserves_mass() {
+        let original = terrain(3);
+        let mut heights = original.clone();
+        ThermalErosion::new(20).erode(&mut heights, SIZE, SIZE);
+
+        let sum = |heights: &[f32]| heights.iter().sum::<f32>();
+        assert!((sum(&heights) - sum(&original)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn thermal_flattens_slopes() {
+        let mut heights = terrain(4);
+        let before = steepest(&heights);
+        ThermalErosion {
+            talus: 0.,
+            ..ThermalErosion::new(50)
+        }
+        .erode(&mut heights, SIZE, SIZE);
+        assert!(steepest(&heights) < before);
+    }
+
+    #[test]
+    fn thermal_keeps_gentle_slopes() {
+        let original = terrain(5);
+        let mut heights = original.clone();
+        ThermalErosion {
+            talus: steepest(&original),
+            ..ThermalErosion::default()
+        }
+        .erode(&mut heights, SIZE, SIZE);
+        assert_eq!(heights, original);
+    }
+}
@@ -0,0 +1,156 @@
+use std::io;
+
+/// Maps a noise value in `[-1, 1]` to an 8-bit gray level, clamping values
+/// outside the range.
+pub fn to_gray(value: f32) -> u8 {
+    let normal = (value.clamp(-1., 1.) + 1.) / 2.;
+    (255. * normal).round() as u8
+}
+
+fn check_size(width: usize, height: usize, values: &[f32]) -> io::Result<()> {
+    if values.len() == width * height {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "expected {width}x{height} = {} values, got {}",
+                width * height,
+                values.len()
+            ),
+        ))
+    }
+}
+
+/// Writes row-major noise `values` as a binary 8-bit grayscale PGM image.
+pub fn write_pgm(
+    out: &mut impl io::Write,
+    width: usize,
+    height: usize,
+    values: &[f32],
+) -> io::Result<()> {
+    check_size(width, height, values)?;
+
+    write!(out, "P5\n{width} {height}\n255\n")?;
+    let pixels: Vec<u8> = values.iter().copied().map(to_gray).collect();
+    out.write_all(&pixels)
+}
+
+/// Writes row-major noise `values` as an 8-bit grayscale PNG image.
+pub fn write_png(
+    out: &mut impl io::Write,
+    width: usize,
+    height: usize,
+    values: &[f32],
+) -> io::Result<()> {
+    check_size(width, height, values)?;
+    let dimension = |size: usize| {
+        u32::try_from(size)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "image too large for a PNG"))
+    };
+
+    let mut encoder = png::Encoder::new(out, dimension(width)?, dimension(height)?);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let pixels: Vec<u8> = values.iter().copied().map(to_gray).collect();
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels)?;
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Writes `values` as consecutive little-endian `f32`s, without a header.
+pub fn write_raw(out: &mut impl io::Write, values: &[f32]) -> io::Result<()> {
+    let bytes: Vec<u8> = values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+    out.write_all(&bytes)
+}
+
+/// Reads back a dump made by [`write_raw`].
+pub fn read_raw(input: &mut impl io::Read) -> io::Result<Vec<f32>> {
+    let mut bytes = vec![];
+    input.read_to_end(&mut bytes)?;
+
+    if bytes.len() % 4 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "raw dump length is not a multiple of 4 bytes",
+        ));
+    }
+
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Perlin;
+
+    fn field(width: usize, height: usize) -> Vec<f32> {
+        let mut values = vec![0.; width * height];
+        Perlin::new(1).fill_grid([0.3, -1.2], 0.17, width, height, &mut values);
+        values
+    }
+
+    #[test]
+    fn gray_range() {
+        assert_eq!(to_gray(-1.), 0);
+        assert_eq!(to_gray(0.), 128);
+        assert_eq!(to_gray(1.), 255);
+        assert_eq!(to_gray(-3.), 0);
+        assert_eq!(to_gray(3.), 255);
+    }
+
+    #[test]
+    fn pgm_bytes() {
+        let mut out = vec![];
+        write_pgm(&mut out, 3, 2, &[-1., 0., 1., 1., 0., -1.]).unwrap();
+        assert_eq!(out, b"P5\n3 2\n255\n\x00\x80\xff\xff\x80\x00");
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let (width, height) = (13, 7);
+        let values = field(width, height);
+        let mut out = vec![];
+        write_png(&mut out, width, height, &values).unwrap();
+
+        let decoder = png::Decoder::new(io::Cursor::new(out));
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+
+        assert_eq!((info.width, info.height), (13, 7));
+        assert_eq!(info.color_type, png::ColorType::Grayscale);
+        let expected: Vec<u8> = values.into_iter().map(to_gray).collect();
+        assert_eq!(&pixels[..info.buffer_size()], expected);
+    }
+
+    #[test]
+    fn raw_round_trip() {
+        let values = field(9, 4);
+        let mut out = vec![];
+        write_raw(&mut out, &values).unwrap();
+
+        assert_eq!(out.len(), values.len() * 4);
+        assert_eq!(&out[..4], values[0].to_le_bytes());
+        assert_eq!(read_raw(&mut out.as_slice()).unwrap(), values);
+    }
+
+    #[test]
+    fn size_mismatch() {
+        let error = write_pgm(&mut vec![], 4, 4, &[0.; 15]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        let error = write_png(&mut vec![], 4, 4, &[0.; 17]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        let error = read_raw(&mut [0u8; 5].as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,105 @@
+use core::{
+    fmt::Debug,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// Floating point precision the gradient noise helpers can be evaluated in.
+///
+/// Implemented for `f32` and `f64`, the latter keeps the fractional cell
+/// offsets exact far away from the origin where `f32` starts banding.
+///
+/// The math functions forward to `std` when it is available and to `libm`
+/// otherwise. The crate calls them through this trait even on concrete
+/// `f32`, so tests without `std` run the `libm` paths.
+pub trait Float:
+    Copy
+    + Debug
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn from_f32(value: f32) -> Self;
+    fn from_i32(value: i32) -> Self;
+    /// Truncating, saturating conversion like `as i32`.
+    fn to_i32(self) -> i32;
+
+    fn floor(self) -> Self;
+    fn mul_add(self, a: Self, b: Self) -> Self;
+    fn clamp(self, min: Self, max: Self) -> Self;
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+}
+
+#[cfg(feature = "std")]
+macro_rules! math {
+    ($float:ty, $std:ident, $libm:ident, $($argument:expr),+) => {
+        <$float>::$std($($argument),+)
+    };
+}
+
+#[cfg(not(feature = "std"))]
+macro_rules! math {
+    ($float:ty, $std:ident, $libm:ident, $($argument:expr),+) => {
+        libm::$libm($($argument),+)
+    };
+}
+
+macro_rules! impl_float {
+    ($float:ty, $floor:ident, $fma:ident, $sqrt:ident, $sin:ident, $cos:ident, $hypot:ident) => {
+        impl Float for $float {
+            const ZERO: Self = 0.;
+            const ONE: Self = 1.;
+
+            fn from_f32(value: f32) -> Self {
+                value as $float
+            }
+
+            fn from_i32(value: i32) -> Self {
+                value as $float
+            }
+
+            fn to_i32(self) -> i32 {
+                self as i32
+            }
+
+            fn floor(self) -> Self {
+                math!($float, floor, $floor, self)
+            }
+
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                math!($float, mul_add, $fma, self, a, b)
+            }
+
+            fn clamp(self, min: Self, max: Self) -> Self {
+                <$float>::clamp(self, min, max)
+            }
+
+            fn sqrt(self) -> Self {
+                math!($float, sqrt, $sqrt, self)
+            }
+
+            fn sin(self) -> Self {
+                math!($float, sin, $sin, self)
+            }
+
+            fn cos(self) -> Self {
+                math!($float, cos, $cos, self)
+            }
+
+            fn hypot(self, other: Self) -> Self {
+                math!($float, hypot, $hypot, self, other)
+            }
+        }
+    };
+}
+
+impl_float!(f32, floorf, fmaf, sqrtf, sinf, cosf, hypotf);
+impl_float!(f64, floor, fma, sqrt, sin, cos, hypot);
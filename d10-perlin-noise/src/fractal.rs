@@ -0,0 +1,237 @@
+use alloc::vec;
+
+use crate::Float;
+use crate::{Noise2D, Perlin};
+
+/// How the octaves of an [`Fbm`] are combined.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FractalKind {
+    /// Plain sum of octaves.
+    #[default]
+    Standard,
+    /// Sharp crests where the noise crosses zero, `(1 - |n|)^2` per octave.
+    Ridged,
+    /// Billowy sum of absolute values, `|n|` per octave.
+    Turbulence,
+}
+
+/// Fractal Brownian motion, layering octaves of any [`Noise2D`] source.
+///
+/// Octaves are divided by [`Noise2D::amplitude`] and every kind is
+/// normalized into `[-1, 1]`.
+#[derive(Clone, Debug)]
+pub struct Fbm<N = Perlin> {
+    pub noise: N,
+    /// Number of octaves layered, at least one is always sampled.
+    pub octaves: u32,
+    /// Frequency of the first octave.
+    pub frequency: f32,
+    /// Frequency multiplier between octaves.
+    pub lacunarity: f32,
+    /// Amplitude multiplier between octaves, also known as gain.
+    pub persistence: f32,
+    pub kind: FractalKind,
+}
+
+impl<N: Noise2D> Fbm<N> {
+    pub fn new(noise: N) -> Self {
+        Self {
+            noise,
+            octaves: 4,
+            frequency: 1.,
+            lacunarity: 2.,
+            persistence: 0.5,
+            kind: FractalKind::Standard,
+        }
+    }
+
+    pub fn sample(&self, #[allow(unused_variables)] point @ [px, py]: [f32; 2]) -> f32 {
+        let mut sum = 0.;
+        let total_amplitude = self.for_each_octave(|frequency, shift, amplitude| {
+            let value = self.noise.sample([
+                Float::mul_add(px, frequency, shift),
+                Float::mul_add(py, frequency, shift),
+            ]);
+            sum = Float::mul_add(self.octave_value(value), amplitude, sum);
+        });
+
+        self.normalize(sum / total_amplitude)
+    }
+
+    /// Samples a grid like [`Noise2D::fill_grid`], handing each octave to the
+    /// inner noise as a whole grid so its batched sampling is kept.
+    pub fn fill_grid(
+        &self,
+        #[allow(unused_variables)] origin @ [ox, oy]: [f32; 2],
+        step: f32,
+        width: usize,
+        height: usize,
+        out: &mut [f32],
+    ) {
+        assert_eq!(
+            out.len(),
+            width * height,
+            "grid buffer must hold width * height samples"
+        );
+
+        let mut octave = vec![0.; out.len()];
+        out.fill(0.);
+
+        let total_amplitude = self.for_each_octave(|frequency, shift, amplitude| {
+            let origin = [
+                Float::mul_add(ox, frequency, shift),
+                Float::mul_add(oy, frequency, shift),
+            ];
+            self.noise
+                .fill_grid(origin, step * frequency, width, height, &mut octave);
+
+            for (sum, &value) in out.iter_mut().zip(&octave) {
+                *sum = Float::mul_add(self.octave_value(value), amplitude, *sum);
+            }
+        });
+
+        for value in out {
+            *value = self.normalize(*value / total_amplitude);
+        }
+    }
+
+    /// Calls `octave` with the frequency, shift and amplitude of every
+    /// octave, returning the summed amplitude.
+    fn for_each_octave(&self, mut octave: impl FnMut(f32, f32, f32)) -> f32 {
+        let mut frequency = self.frequency;
+        let mut amplitude = 1.;
+        let mut total_amplitude = 0.;
+
+        for index in 0..self.octaves.max(1) {
+            // shift every octave so their lattice zeroes do not line up
+            let shift = index as f32 * 19.19;
+            octave(frequency, shift, amplitude);
+
+            total_amplitude += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.persistence;
+        }
+
+        total_amplitude
+    }
+
+    fn octave_value(&self, value: f32) -> f32 {
+        let value = value / self.noise.amplitude();
+        match self.kind {
+            FractalKind::Standard => value,
+            FractalKind::Ridged => {
+                let ridge = 1. - value.abs();
+                ridge * ridge
+            }
+            FractalKind::Turbulence => value.abs(),
+        }
+    }
+
+    fn normalize(&self, normal: f32) -> f32 {
+        let normal = match self.kind {
+            FractalKind::Standard => normal,
+            FractalKind::Ridged | FractalKind::Turbulence => Float::mul_add(normal, 2., -1.),
+        };
+
+        normal.clamp(-1., 1.)
+    }
+}
+
+impl<N: Noise2D> Noise2D for Fbm<N> {
+    fn sample(&self, point: [f32; 2]) -> f32 {
+        Fbm::sample(self, point)
+    }
+
+    fn fill_grid(&self, origin: [f32; 2], step: f32, width: usize, height: usize, out: &mut [f32]) {
+        Fbm::fill_grid(self, origin, step, width, height, out)
+    }
+}
+
+impl Default for Fbm {
+    fn default() -> Self {
+        Self::new(Perlin::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_points, Simplex};
+
+    #[test]
+    fn normalized_range() {
+        for kind in [
+            FractalKind::Standard,
+            FractalKind::Ridged,
+            FractalKind::Turbulence,
+        ] {
+            let fbm = Fbm {
+                octaves: 6,
+                kind,
+                ..Fbm::default()
+            };
+            assert!(test_points::lattice(32, [0.173, 0.291])
+                .all(|point| (-1. ..=1.).contains(&fbm.sample(point))));
+        }
+    }
+
+    #[test]
+    fn single_octave_is_scaled_perlin() {
+        let fbm = Fbm {
+            octaves: 1,
+            ..Fbm::default()
+        };
+        for point in test_points::lattice(32, [0.173, 0.291]) {
+            let expected = fbm.noise.sample(point) / core::f32::consts::FRAC_1_SQRT_2;
+            assert!((fbm.sample(point) - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn layers_simplex() {
+        let fbm = Fbm {
+            octaves: 5,
+            kind: FractalKind::Ridged,
+            ..Fbm::new(Simplex::new(3))
+        };
+        assert!(test_points::lattice(32, [0.173, 0.291])
+            .all(|point| (-1. ..=1.).contains(&fbm.sample(point))));
+    }
+
+    #[test]
+    fn fill_grid_matches_sample() {
+        let fbm = Fbm {
+            octaves: 5,
+            kind: FractalKind::Turbulence,
+            ..Fbm::default()
+        };
+        let (width, height) = (23, 17);
+        let mut grid = vec![0.; width * height];
+        fbm.fill_grid([-1.3, 2.2], 0.11, width, height, &mut grid);
+
+        for (y, row) in grid.chunks_exact(width).enumerate() {
+            for (x, &value) in row.iter().enumerate() {
+                let point = [
+                    0.11f32.mul_add(x as f32, -1.3),
+                    0.11f32.mul_add(y as f32, 2.2),
+                ];
+                assert!((value - fbm.sample(point)).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn octaves_add_detail() {
+        let smooth = Fbm {
+            octaves: 1,
+            ..Fbm::default()
+        };
+        let rough = Fbm {
+            octaves: 6,
+            ..Fbm::default()
+        };
+        let differs = test_points::lattice(32, [0.173, 0.291])
+            .any(|point| smooth.sample(point) != rough.sample(point));
+        assert!(differs);
+    }
+}
@@ -0,0 +1,181 @@
+use alloc::{vec, vec::Vec};
+
+use crate::Float;
+use crate::{blerp, dot, Perlin};
+
+/// Point by point grid sampling backing [`Noise2D::fill_grid`](crate::Noise2D::fill_grid).
+pub(crate) fn fill_grid_with(
+    #[allow(unused_variables)] origin @ [ox, oy]: [f32; 2],
+    step: f32,
+    width: usize,
+    height: usize,
+    out: &mut [f32],
+    sample: impl Fn([f32; 2]) -> f32,
+) {
+    assert_grid(width, height, out);
+
+    for (y, row) in out.chunks_exact_mut(width.max(1)).enumerate() {
+        let py = Float::mul_add(step, y as f32, oy);
+        for (x, value) in row.iter_mut().enumerate() {
+            *value = sample([Float::mul_add(step, x as f32, ox), py]);
+        }
+    }
+}
+
+fn assert_grid(width: usize, height: usize, out: &[f32]) {
+    assert_eq!(
+        out.len(),
+        width * height,
+        "grid buffer must hold width * height samples"
+    );
+}
+
+impl Perlin {
+    /// Samples a grid like [`Noise2D::fill_grid`](crate::Noise2D::fill_grid).
+    ///
+    /// Column offsets and fade weights are computed once for the whole grid
+    /// and the corner gradients once per row of cells, leaving a tight inner
+    /// loop over contiguous slices for the compiler to vectorize.
+    pub fn fill_grid(
+        &self,
+        #[allow(unused_variables)] origin @ [ox, oy]: [f32; 2],
+        step: f32,
+        width: usize,
+        height: usize,
+        out: &mut [f32],
+    ) {
+        assert_grid(width, height, out);
+        if out.is_empty() {
+            return;
+        }
+
+        let cells: Vec<i32> = (0..width)
+            .map(|x| Float::floor(Float::mul_add(step, x as f32, ox)) as i32)
+            .collect();
+        let offsets: Vec<f32> = (0..width)
+            .zip(&cells)
+            .map(|(x, &cell)| Float::mul_add(step, x as f32, ox) - cell as f32)
+            .collect();
+        let weights: Vec<f32> = offsets
+            .iter()
+            .map(|&offset| self.interpolation().fade(offset))
+            .collect();
+
+        // only the cells actually sampled are cached, so large steps don't
+        // fill a row with every cell they skip over; cells are monotonic in x
+        let mut columns: Vec<i32> = cells.clone();
+        columns.dedup();
+        let mut column = 0;
+        let indices: Vec<usize> = cells
+            .iter()
+            .map(|&cell| {
+                if columns[column] != cell {
+                    column += 1;
+                }
+                column
+            })
+            .collect();
+
+        // left and right corner gradients of every column
+        let mut bottom = vec![[[0.; 2]; 2]; columns.len()];
+        let mut top = vec![[[0.; 2]; 2]; columns.len()];
+        let mut cached_row = None;
+
+        for (y, row) in out.chunks_exact_mut(width).enumerate() {
+            let py = Float::mul_add(step, y as f32, oy);
+            let gy = Float::floor(py) as i32;
+
+            if cached_row != Some(gy) {
+                if cached_row == Some(gy - 1) {
+                    core::mem::swap(&mut bottom, &mut top);
+                } else {
+                    self.fill_gradient_row(&columns, gy, &mut bottom);
+                }
+                self.fill_gradient_row(&columns, gy + 1, &mut top);
+                cached_row = Some(gy);
+            }
+
+            let dy = py - gy as f32;
+            let y_weight = self.interpolation().fade(dy);
+
+            for (value, ((&cell, &dx), &x_weight)) in row
+                .iter_mut()
+                .zip(indices.iter().zip(&offsets).zip(&weights))
+            {
+                // grid bottom left, bottom right, top left, top right
+                let [bl, br] = bottom[cell];
+                let [tl, tr] = top[cell];
+                let gbl = dot([dx, dy], bl);
+                let gbr = dot([dx - 1., dy], br);
+                let gtl = dot([dx, dy - 1.], tl);
+                let gtr = dot([dx - 1., dy - 1.], tr);
+
+                *value = blerp(gtl, gtr, gbl, gbr, x_weight, y_weight);
+            }
+        }
+    }
+
+    fn fill_gradient_row(&self, columns: &[i32], gy: i32, gradients: &mut [[[f32; 2]; 2]]) {
+        for (&gx, corners) in columns.iter().zip(gradients) {
+            *corners = [self.gradient([gx, gy]), self.gradient([gx + 1, gy])];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Noise2D, Simplex};
+
+    fn assert_matches_sample(noise: &impl Noise2D, origin: [f32; 2], step: f32) {
+        let (width, height) = (37, 29);
+        let mut grid = vec![0.; width * height];
+        noise.fill_grid(origin, step, width, height, &mut grid);
+
+        for (y, row) in grid.chunks_exact(width).enumerate() {
+            for (x, &value) in row.iter().enumerate() {
+                let point = [
+                    step.mul_add(x as f32, origin[0]),
+                    step.mul_add(y as f32, origin[1]),
+                ];
+                assert!((value - noise.sample(point)).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn perlin_matches_sample() {
+        let noise = Perlin::new(8);
+        assert_matches_sample(&noise, [0., 0.], 0.25);
+        assert_matches_sample(&noise, [-3.7, 12.1], 0.13);
+        assert_matches_sample(&noise, [5.5, -2.5], 1.7);
+        assert_matches_sample(&noise, [4., 4.], -0.3);
+    }
+
+    #[test]
+    fn perlin_large_steps() {
+        let noise = Perlin::new(8);
+        assert_matches_sample(&noise, [0.5, 0.5], 100.25);
+        assert_matches_sample(&noise, [-7.3, 2.2], -1e4);
+        // a cache spanning every skipped cell would need gigabytes here
+        let mut grid = vec![0.; 4];
+        noise.fill_grid([0.5, 0.5], 1e9, 2, 2, &mut grid);
+    }
+
+    #[test]
+    fn default_matches_sample() {
+        assert_matches_sample(&Simplex::new(8), [-1., 2.], 0.21);
+    }
+
+    #[test]
+    fn empty_grid() {
+        Perlin::new(0).fill_grid([0., 0.], 1., 0, 4, &mut []);
+        Perlin::new(0).fill_grid([0., 0.], 1., 4, 0, &mut []);
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_buffer_size() {
+        Perlin::new(0).fill_grid([0., 0.], 1., 4, 4, &mut [0.; 15]);
+    }
+}
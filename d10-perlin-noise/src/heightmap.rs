@@ -0,0 +1,253 @@
+use alloc::{vec, vec::Vec};
+use core::ops::{Index, IndexMut};
+
+use crate::Float;
+use crate::{lerp, Noise2D};
+
+/// Row-major grid of heights, for composing terrain from noise, erosion and
+/// other generators without hand-rolled loops.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heightmap {
+    width: usize,
+    height: usize,
+    values: Vec<f32>,
+}
+
+impl Heightmap {
+    /// Flat map of zeroes.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            values: vec![0.; width * height],
+        }
+    }
+
+    /// Wraps row-major `values`.
+    ///
+    /// # Panics
+    ///
+    /// If `values` does not hold exactly `width * height` heights.
+    pub fn from_values(width: usize, height: usize, values: Vec<f32>) -> Self {
+        assert_eq!(
+            values.len(),
+            width * height,
+            "heightmap must hold width * height values"
+        );
+        Self {
+            width,
+            height,
+            values,
+        }
+    }
+
+    /// Samples `noise` on a grid of points `step` apart starting at `origin`,
+    /// see [`Noise2D::fill_grid`].
+    pub fn from_noise(
+        noise: &impl Noise2D,
+        origin: [f32; 2],
+        step: f32,
+        width: usize,
+        height: usize,
+    ) -> Self {
+        let mut map = Self::new(width, height);
+        map.fill_from_noise(noise, origin, step);
+        map
+    }
+
+    /// Overwrites every height with a sample of `noise`, like
+    /// [`Heightmap::from_noise`].
+    pub fn fill_from_noise(&mut self, noise: &impl Noise2D, origin: [f32; 2], step: f32) {
+        noise.fill_grid(origin, step, self.width, self.height, &mut self.values);
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    pub fn values_mut(&mut self) -> &mut [f32] {
+        &mut self.values
+    }
+
+    pub fn into_values(self) -> Vec<f32> {
+        self.values
+    }
+
+    pub fn get(&self, [x, y]: [usize; 2]) -> Option<f32> {
+        (x < self.width && y < self.height).then(|| self.values[y * self.width + x])
+    }
+
+    /// Lowest and highest height, `None` for an empty map.
+    pub fn min_max(&self) -> Option<(f32, f32)> {
+        let mut values = self.values.iter().copied();
+        let first = values.next()?;
+        Some(values.fold((first, first), |(min, max), value| {
+            (min.min(value), max.max(value))
+        }))
+    }
+
+    /// Linearly stretches the heights so they span `[min, max]`. A flat map
+    /// is set to `min`.
+    pub fn normalize(&mut self, min: f32, max: f32) {
+        let Some((low, high)) = self.min_max() else {
+            return;
+        };
+        let range = high - low;
+        for value in &mut self.values {
+            let normal = if range > 0. {
+                (*value - low) / range
+            } else {
+                0.
+            };
+            *value = Float::mul_add(normal, max - min, min);
+        }
+    }
+
+    /// Adds the heights of `other` cell by cell.
+    ///
+    /// # Panics
+    ///
+    /// If the maps differ in size, as for every operation on two maps.
+    pub fn add(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a + b);
+    }
+
+    /// Multiplies by the heights of `other` cell by cell, for masking.
+    pub fn multiply(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a * b);
+    }
+
+    /// Interpolates towards `other`, `weight` 0 keeps this map and 1 takes
+    /// the other one.
+    pub fn blend(&mut self, other: &Self, weight: f32) {
+        self.zip_with(other, |a, b| lerp(a, b, weight));
+    }
+
+    /// Passes every height through `curve`, e.g. `|h| h * h` to flatten
+    /// valleys or a terrace step function.
+    pub fn remap(&mut self, curve: impl Fn(f32) -> f32) {
+        for value in &mut self.values {
+            *value = curve(*value);
+        }
+    }
+
+    /// Row-major mask of the cells at or above `level`.
+    pub fn threshold(&self, level: f32) -> Vec<bool> {
+        self.values.iter().map(|&value| value >= level).collect()
+    }
+
+    fn zip_with(&mut self, other: &Self, combine: impl Fn(f32, f32) -> f32) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "heightmaps must be the same size"
+        );
+        for (value, &other) in self.values.iter_mut().zip(&other.values) {
+            *value = combine(*value, other);
+        }
+    }
+}
+
+impl Index<[usize; 2]> for Heightmap {
+    type Output = f32;
+
+    /// # Panics
+    ///
+    /// If the point lies outside the map.
+    fn index(&self, [x, y]: [usize; 2]) -> &f32 {
+        assert!(x < self.width && y < self.height, "point outside heightmap");
+        &self.values[y * self.width + x]
+    }
+}
+
+impl IndexMut<[usize; 2]> for Heightmap {
+    fn index_mut(&mut self, [x, y]: [usize; 2]) -> &mut f32 {
+        assert!(x < self.width && y < self.height, "point outside heightmap");
+        &mut self.values[y * self.width + x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Perlin;
+
+    fn ramp() -> Heightmap {
+        Heightmap::from_values(3, 2, vec![0., 1., 2., 3., 4., 5.])
+    }
+
+    #[test]
+    fn indexing() {
+        let mut map = ramp();
+        assert_eq!(map[[2, 0]], 2.);
+        assert_eq!(map[[0, 1]], 3.);
+        assert_eq!(map.get([2, 1]), Some(5.));
+        assert_eq!(map.get([3, 0]), None);
+
+        map[[1, 1]] = -1.;
+        assert_eq!(map.values(), [0., 1., 2., 3., -1., 5.]);
+    }
+
+    #[test]
+    #[should_panic(expected = "point outside heightmap")]
+    fn index_outside() {
+        let _ = ramp()[[3, 0]];
+    }
+
+    #[test]
+    fn from_noise_matches_samples() {
+        let noise = Perlin::new(6);
+        let map = Heightmap::from_noise(&noise, [0.5, -1.], 0.25, 5, 4);
+        assert_eq!((map.width(), map.height()), (5, 4));
+        assert!((map[[3, 2]] - noise.sample([1.25, -0.5])).abs() < 1e-6);
+    }
+
+    #[test]
+    fn normalize_range() {
+        let mut map = ramp();
+        assert_eq!(map.min_max(), Some((0., 5.)));
+        map.normalize(-1., 1.);
+        assert_eq!(map.min_max(), Some((-1., 1.)));
+        assert!((map[[0, 1]] - 0.2).abs() < 1e-6);
+
+        let mut flat = Heightmap::new(2, 2);
+        flat.normalize(-1., 1.);
+        assert!(flat.values().iter().all(|&value| value == -1.));
+        assert_eq!(Heightmap::new(0, 0).min_max(), None);
+    }
+
+    #[test]
+    fn combine_maps() {
+        let mut map = ramp();
+        map.add(&ramp());
+        assert_eq!(map.values(), [0., 2., 4., 6., 8., 10.]);
+
+        map.multiply(&Heightmap::from_values(3, 2, vec![1., 0., 1., 0., 1., 0.]));
+        assert_eq!(map.values(), [0., 0., 4., 0., 8., 0.]);
+
+        map.blend(&Heightmap::new(3, 2), 0.25);
+        assert_eq!(map.values(), [0., 0., 3., 0., 6., 0.]);
+    }
+
+    #[test]
+    #[should_panic(expected = "heightmaps must be the same size")]
+    fn combine_mismatched() {
+        ramp().add(&Heightmap::new(2, 3));
+    }
+
+    #[test]
+    fn remap_and_threshold() {
+        let mut map = ramp();
+        map.remap(|value| value * value);
+        assert_eq!(map.values(), [0., 1., 4., 9., 16., 25.]);
+        assert_eq!(map.threshold(4.), [false, false, true, true, true, true]);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::Float;
+
+/// Fade curve applied to the fractional cell offsets before interpolating
+/// between the lattice gradients.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Raw offsets, creases along every cell boundary.
+    Linear,
+    /// Cubic smoothstep `3t^2 - 2t^3`, continuous first derivative.
+    Smoothstep,
+    /// Ken Perlin's improved fade `6t^5 - 15t^4 + 10t^3`, continuous first
+    /// and second derivatives.
+    #[default]
+    Quintic,
+}
+
+impl Interpolation {
+    pub fn fade<F: Float>(self, t: F) -> F {
+        let c = F::from_f32;
+        match self {
+            Self::Linear => t,
+            Self::Smoothstep => t * t * c(-2.).mul_add(t, c(3.)),
+            Self::Quintic => t * t * t * t.mul_add(t.mul_add(c(6.), c(-15.)), c(10.)),
+        }
+    }
+
+    /// Slope of [`Interpolation::fade`] at `t`.
+    pub fn derivative<F: Float>(self, t: F) -> F {
+        let c = F::from_f32;
+        match self {
+            Self::Linear => F::ONE,
+            Self::Smoothstep => c(6.) * t * (F::ONE - t),
+            Self::Quintic => c(30.) * t * t * (t - F::ONE) * (t - F::ONE),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Perlin;
+
+    const ALL: [Interpolation; 3] = [
+        Interpolation::Linear,
+        Interpolation::Smoothstep,
+        Interpolation::Quintic,
+    ];
+
+    // one sided second order finite differences, f[0] is the evaluation point
+    fn first_derivative(f: [f32; 3], h: f32) -> f32 {
+        (3. * f[0] - 4. * f[1] + f[2]) / (2. * h)
+    }
+
+    fn second_derivative(f: [f32; 4], h: f32) -> f32 {
+        (2. * f[0] - 5. * f[1] + 4. * f[2] - f[3]) / (h * h)
+    }
+
+    /// Largest jump of the `order`-th x derivative across vertical cell
+    /// boundaries, sampled along several rows.
+    fn boundary_jump(noise: &Perlin, order: usize, h: f32) -> f32 {
+        let mut jump = 0f32;
+        for boundary in -3..3 {
+            for row in 0..8 {
+                let x = boundary as f32;
+                let y = row as f32 * 0.37 + 0.1;
+                let left: [f32; 4] = core::array::from_fn(|i| noise.sample([x - i as f32 * h, y]));
+                let right: [f32; 4] = core::array::from_fn(|i| noise.sample([x + i as f32 * h, y]));
+
+                let (left, right) = match order {
+                    1 => (
+                        first_derivative([left[0], left[1], left[2]], h),
+                        first_derivative([right[0], right[1], right[2]], -h),
+                    ),
+                    _ => (second_derivative(left, h), second_derivative(right, h)),
+                };
+                jump = jump.max((left - right).abs());
+            }
+        }
+        jump
+    }
+
+    #[test]
+    fn fade_endpoints() {
+        for interpolation in ALL {
+            assert_eq!(interpolation.fade(0.), 0.);
+            assert_eq!(interpolation.fade(1.), 1.);
+            assert!((interpolation.fade(0.5f32) - 0.5).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn derivative_matches_fade() {
+        let h = 1e-3;
+        for interpolation in ALL {
+            for i in 1..100 {
+                let t = i as f32 / 100.;
+                let slope = (interpolation.fade(t + h) - interpolation.fade(t - h)) / (2. * h);
+                assert!((interpolation.derivative(t) - slope).abs() < 1e-2);
+            }
+        }
+    }
+
+    #[test]
+    fn fade_monotonic() {
+        for interpolation in ALL {
+            let mut previous = 0.;
+            for i in 1..=100 {
+                let value = interpolation.fade(i as f32 / 100.);
+                assert!(value >= previous);
+                previous = value;
+            }
+        }
+    }
+
+    #[test]
+    fn linear_has_creases() {
+        let noise = Perlin::new(5).with_interpolation(Interpolation::Linear);
+        assert!(boundary_jump(&noise, 1, 5e-3) > 0.1);
+    }
+
+    #[test]
+    fn smoothstep_c1_continuous() {
+        let noise = Perlin::new(5).with_interpolation(Interpolation::Smoothstep);
+        assert!(boundary_jump(&noise, 1, 5e-3) < 1e-2);
+        assert!(boundary_jump(&noise, 2, 5e-3) > 1.);
+    }
+
+    #[test]
+    fn quintic_c2_continuous() {
+        let noise = Perlin::new(5);
+        assert_eq!(noise.interpolation(), Interpolation::Quintic);
+        assert!(boundary_jump(&noise, 1, 5e-3) < 1e-2);
+        assert!(boundary_jump(&noise, 2, 5e-3) < 0.5);
+    }
+}
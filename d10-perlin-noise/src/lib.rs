@@ -1,49 +1,514 @@
-pub fn lerp(a: f32, b: f32, weight: f32) -> f32 {
-    weight.clamp(0., 1.).mul_add(b - a, a)
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+mod cave;
+mod derivative;
+mod dla;
+mod erosion;
+#[cfg(feature = "std")]
+pub mod export;
+mod float;
+mod fractal;
+mod grid;
+mod heightmap;
+mod interpolation;
+mod periodic;
+mod rng;
+mod simplex;
+mod table;
+#[cfg(test)]
+mod test_points;
+mod value;
+mod warp;
+mod white;
+mod worley;
+
+pub use cave::{Cave, CaveMap, Tile};
+pub use dla::Dla;
+pub use erosion::{HydraulicErosion, ThermalErosion};
+pub use float::Float;
+pub use fractal::{Fbm, FractalKind};
+pub use heightmap::Heightmap;
+pub use interpolation::Interpolation;
+pub use periodic::Periodic;
+pub use rng::{NoiseRng, SplitMix64};
+pub use simplex::Simplex;
+pub use table::PermutationTable;
+pub use value::Value;
+pub use warp::Warp;
+pub use white::White;
+pub use worley::{Worley, WorleyDistance, WorleyFeature};
+
+/// Seed used by the free `perlin` function and friends, which need the
+/// `std` feature for their lazily built generator.
+pub const DEFAULT_SEED: u64 = 0;
+
+pub fn lerp<F: Float>(a: F, b: F, weight: F) -> F {
+    weight.clamp(F::ZERO, F::ONE).mul_add(b - a, a)
 }
 
-pub fn blerp(tl: f32, tr: f32, bl: f32, br: f32, x_weight: f32, y_weight: f32) -> f32 {
+pub fn blerp<F: Float>(tl: F, tr: F, bl: F, br: F, x_weight: F, y_weight: F) -> F {
     let lower = lerp(bl, br, x_weight);
     let upper = lerp(tl, tr, x_weight);
-    let middle = lerp(lower, upper, y_weight);
 
-    middle
+    lerp(lower, upper, y_weight)
+}
+
+/// Interpolates between the `2^N` corners of a unit hypercube.
+///
+/// Bit `i` of the index handed to `corner` selects the far side along axis
+/// `i`, the weights are applied axis by axis like [`blerp`] does for 2D.
+pub fn lerp_n<F: Float, const N: usize>(corner: impl Fn(usize) -> F, weights: [F; N]) -> F {
+    fn interpolate<F: Float>(corner: &impl Fn(usize) -> F, weights: &[F], base: usize) -> F {
+        match weights.split_last() {
+            None => corner(base),
+            Some((&weight, rest)) => {
+                let near = interpolate(corner, rest, base);
+                let far = interpolate(corner, rest, base | 1 << rest.len());
+                lerp(near, far, weight)
+            }
+        }
+    }
+
+    interpolate(&corner, &weights, 0)
+}
+
+#[cfg(feature = "std")]
+pub fn random_gradient<const N: usize>(grid_point: [i32; N]) -> [f32; N] {
+    default_perlin().gradient(grid_point)
+}
+
+pub fn dot<F: Float, const N: usize>(a: [F; N], b: [F; N]) -> F {
+    a.into_iter()
+        .zip(b)
+        .fold(F::ZERO, |sum, (a, b)| a.mul_add(b, sum))
+}
+
+#[cfg(feature = "std")]
+pub fn grid_point_gradient<F: Float, const N: usize>(grid_point: [i32; N], point: [F; N]) -> F {
+    default_perlin().grid_point_gradient(grid_point, point)
+}
+
+#[cfg(feature = "std")]
+/// Samples the noise field of the [`DEFAULT_SEED`] generator.
+pub fn perlin<F: Float>(point: [F; 2]) -> F {
+    default_perlin().sample(point)
+}
+
+#[cfg(feature = "std")]
+/// Samples the [`DEFAULT_SEED`] noise field along with its partial
+/// derivatives, see [`Perlin::sample_with_derivative`].
+pub fn perlin_with_derivative(point: [f32; 2]) -> (f32, [f32; 2]) {
+    default_perlin().sample_with_derivative(point)
 }
 
-pub fn random_gradient(#[allow(unused_variables)] grid_point @ [x, y]: [i32; 2]) -> [f32; 2] {
-    fastrand::seed((x as u64) << 32 | y as u64);
-    let random = fastrand::f32() * 2. * std::f32::consts::PI;
-    [random.cos(), random.sin()]
+#[cfg(feature = "std")]
+pub fn perlin1<F: Float>(x: F) -> F {
+    default_perlin().sample1(x)
 }
 
-pub fn dot(
-    #[allow(unused_variables)] a @ [x1, y1]: [f32; 2],
-    #[allow(unused_variables)] b @ [x2, y2]: [f32; 2],
-) -> f32 {
-    x1.mul_add(x2, y1 * y2)
+#[cfg(feature = "std")]
+pub fn perlin3<F: Float>(point: [F; 3]) -> F {
+    default_perlin().sample3(point)
 }
 
-pub fn grid_point_gradient(
-    #[allow(unused_variables)] grid_point @ [gx, gy]: [i32; 2],
-    #[allow(unused_variables)] point @ [px, py]: [f32; 2],
-) -> f32 {
-    let grid_point_gradient = random_gradient(grid_point);
-    let grid_point_distance = [px - gx as f32, py - gy as f32];
-    dot(grid_point_distance, grid_point_gradient)
+#[cfg(feature = "std")]
+pub fn perlin4<F: Float>(point: [F; 4]) -> F {
+    default_perlin().sample4(point)
 }
 
-pub fn perlin(point @ [px, py]: [f32; 2]) -> f32 {
-    let (gx1, gy1) = (px.floor() as i32, py.floor() as i32);
-    let (gx2, gy2) = (gx1 + 1, gy1 + 1);
+/// Two dimensional noise source, shared by every algorithm in this crate so
+/// callers can switch between them.
+pub trait Noise2D {
+    fn sample(&self, point: [f32; 2]) -> f32;
+
+    /// Largest magnitude [`Noise2D::sample`] can return.
+    fn amplitude(&self) -> f32 {
+        1.
+    }
+
+    /// Samples a `width` by `height` grid of points `step` apart starting at
+    /// `origin`, writing the rows one after another into `out`.
+    ///
+    /// # Panics
+    ///
+    /// If `out` does not hold exactly `width * height` values.
+    fn fill_grid(&self, origin: [f32; 2], step: f32, width: usize, height: usize, out: &mut [f32]) {
+        grid::fill_grid_with(origin, step, width, height, out, |point| self.sample(point))
+    }
+}
+
+impl<T: Noise2D + ?Sized> Noise2D for &T {
+    fn sample(&self, point: [f32; 2]) -> f32 {
+        (**self).sample(point)
+    }
+
+    fn amplitude(&self) -> f32 {
+        (**self).amplitude()
+    }
+
+    fn fill_grid(&self, origin: [f32; 2], step: f32, width: usize, height: usize, out: &mut [f32]) {
+        (**self).fill_grid(origin, step, width, height, out)
+    }
+}
+
+impl<T: Noise2D + ?Sized> Noise2D for Box<T> {
+    fn sample(&self, point: [f32; 2]) -> f32 {
+        (**self).sample(point)
+    }
+
+    fn amplitude(&self) -> f32 {
+        (**self).amplitude()
+    }
+
+    fn fill_grid(&self, origin: [f32; 2], step: f32, width: usize, height: usize, out: &mut [f32]) {
+        (**self).fill_grid(origin, step, width, height, out)
+    }
+}
+
+#[cfg(feature = "std")]
+fn default_perlin() -> &'static Perlin {
+    static DEFAULT: OnceLock<Perlin> = OnceLock::new();
+    DEFAULT.get_or_init(|| Perlin::new(DEFAULT_SEED))
+}
+
+/// Seeded Perlin noise generator.
+///
+/// Owns its own permutation and gradient tables, so sampling never touches
+/// any global random state.
+#[derive(Clone, Debug)]
+pub struct Perlin {
+    table: PermutationTable,
+    interpolation: Interpolation,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        Self::from_table(PermutationTable::new(seed))
+    }
+
+    /// Builds the generator on an existing table, for example one made with
+    /// [`PermutationTable::with_rng`].
+    pub fn from_table(table: PermutationTable) -> Self {
+        Self {
+            table,
+            interpolation: Interpolation::default(),
+        }
+    }
+
+    pub fn with_interpolation(self, interpolation: Interpolation) -> Self {
+        Self {
+            interpolation,
+            ..self
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.table.seed()
+    }
+
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Pseudo-random unit gradient assigned to a lattice point, see
+    /// [`PermutationTable::gradient`].
+    pub fn gradient<const N: usize>(&self, grid_point: [i32; N]) -> [f32; N] {
+        self.table.gradient(grid_point)
+    }
+
+    pub fn grid_point_gradient<F: Float, const N: usize>(
+        &self,
+        grid_point: [i32; N],
+        point: [F; N],
+    ) -> F {
+        let grid_point_gradient = self.gradient(grid_point).map(F::from_f32);
+        let grid_point_distance = core::array::from_fn(|i| point[i] - F::from_i32(grid_point[i]));
+        dot(grid_point_distance, grid_point_gradient)
+    }
+
+    pub fn sample<F: Float>(&self, point @ [px, py]: [F; 2]) -> F {
+        let (gx1, gy1) = (px.floor().to_i32(), py.floor().to_i32());
+        let (gx2, gy2) = (gx1 + 1, gy1 + 1);
+
+        let x_weight = self.interpolation.fade(px - F::from_i32(gx1));
+        let y_weight = self.interpolation.fade(py - F::from_i32(gy1));
+
+        // grid bottom left, bottom right, top left, top right
+        let gbl = self.grid_point_gradient([gx1, gy1], point);
+        let gbr = self.grid_point_gradient([gx2, gy1], point);
+        let gtl = self.grid_point_gradient([gx1, gy2], point);
+        let gtr = self.grid_point_gradient([gx2, gy2], point);
+
+        blerp(gtl, gtr, gbl, gbr, x_weight, y_weight)
+    }
+
+    pub fn sample1<F: Float>(&self, x: F) -> F {
+        self.sample_n([x])
+    }
+
+    pub fn sample3<F: Float>(&self, point: [F; 3]) -> F {
+        self.sample_n(point)
+    }
+
+    pub fn sample4<F: Float>(&self, point: [F; 4]) -> F {
+        self.sample_n(point)
+    }
+
+    /// Samples `N` dimensional noise, interpolating all `2^N` surrounding
+    /// lattice points. Values stay within `±sqrt(N) / 2`.
+    pub fn sample_n<F: Float, const N: usize>(&self, point: [F; N]) -> F {
+        self.sample_lattice(point, |grid_point| grid_point)
+    }
+
+    /// Like [`Perlin::sample_n`], but the lattice wraps every `period` cells
+    /// along each axis so the field tiles seamlessly.
+    ///
+    /// # Panics
+    ///
+    /// If any period is zero or above `i32::MAX`.
+    pub fn sample_periodic_n<F: Float, const N: usize>(
+        &self,
+        point: [F; N],
+        period: [u32; N],
+    ) -> F {
+        let period = period.map(|period| {
+            assert!(period > 0, "noise period must be non-zero");
+            i32::try_from(period).expect("noise period must fit in an i32")
+        });
+
+        self.sample_lattice(point, |grid_point| {
+            core::array::from_fn(|i| grid_point[i].rem_euclid(period[i]))
+        })
+    }
+
+    /// Interpolates the gradients surrounding `point`, looking each one up
+    /// at the lattice point returned by `wrap`.
+    fn sample_lattice<F: Float, const N: usize>(
+        &self,
+        point: [F; N],
+        wrap: impl Fn([i32; N]) -> [i32; N],
+    ) -> F {
+        let base = point.map(|p| p.floor().to_i32());
+        let weights: [F; N] =
+            core::array::from_fn(|i| self.interpolation.fade(point[i] - F::from_i32(base[i])));
+
+        lerp_n(
+            |corner| {
+                let grid_point = core::array::from_fn(|i| base[i] + (corner >> i & 1) as i32);
+                let grid_point_distance =
+                    core::array::from_fn(|i| point[i] - F::from_i32(grid_point[i]));
+                let gradient = self.gradient(wrap(grid_point)).map(F::from_f32);
+                dot(grid_point_distance, gradient)
+            },
+            weights,
+        )
+    }
+}
+
+impl Noise2D for Perlin {
+    fn sample(&self, point: [f32; 2]) -> f32 {
+        Perlin::sample(self, point)
+    }
+
+    fn amplitude(&self) -> f32 {
+        core::f32::consts::FRAC_1_SQRT_2
+    }
+
+    fn fill_grid(&self, origin: [f32; 2], step: f32, width: usize, height: usize, out: &mut [f32]) {
+        Perlin::fill_grid(self, origin, step, width, height, out)
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn same_seed_same_noise() {
+        let a = Perlin::new(42);
+        let b = Perlin::new(42);
+        for i in 0..64 {
+            let point = [i as f32 * 0.37, i as f32 * 0.71];
+            assert_eq!(a.sample(point), b.sample(point));
+        }
+    }
+
+    #[test]
+    fn different_seed_different_noise() {
+        let a = Perlin::new(1);
+        let b = Perlin::new(2);
+        let differs = (0..64)
+            .map(|i| [i as f32 * 0.37, i as f32 * 0.71])
+            .any(|point| a.sample(point) != b.sample(point));
+        assert!(differs);
+    }
+
+    #[test]
+    fn zero_on_lattice() {
+        let noise = Perlin::new(7);
+        for x in -4..4 {
+            for y in -4..4 {
+                assert_eq!(noise.sample([x as f32, y as f32]), 0.);
+            }
+        }
+    }
+
+    #[test]
+    fn unit_gradients() {
+        let noise = Perlin::new(3);
+        for x in -8..8 {
+            for y in -8..8 {
+                let gradient = noise.gradient([x, y]);
+                assert!((dot(gradient, gradient) - 1.).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn unit_gradients_n() {
+        let noise = Perlin::new(3);
+        for x in -8..8 {
+            for y in -8..8 {
+                let g1 = noise.gradient([x]);
+                let g3 = noise.gradient([x, y, x ^ y]);
+                let g4 = noise.gradient([x, y, x - y, x + y]);
+                assert!(g1[0].abs() <= 1.);
+                assert!((dot(g3, g3) - 1.).abs() < 1e-5);
+                assert!((dot(g4, g4) - 1.).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn lerp_n_matches_blerp() {
+        let corners = [0.1, -0.4, 0.7, 0.25];
+        for (x_weight, y_weight) in [(0., 0.), (0.3, 0.8), (1., 0.5), (0.9, 0.1)] {
+            let [bl, br, tl, tr] = corners;
+            assert_eq!(
+                lerp_n(|corner| corners[corner], [x_weight, y_weight]),
+                blerp(tl, tr, bl, br, x_weight, y_weight),
+            );
+        }
+    }
+
+    #[test]
+    fn sample_n_matches_sample() {
+        let noise = Perlin::new(11);
+        for i in 0..64 {
+            let point = [i as f32 * 0.37 - 8., i as f32 * 0.71 - 3.];
+            assert!((noise.sample_n(point) - noise.sample(point)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn zero_on_lattice_n() {
+        let noise = Perlin::new(7);
+        for x in -3..3 {
+            for y in -3..3 {
+                let (x, y) = (x as f32, y as f32);
+                assert_eq!(noise.sample1(x), 0.);
+                assert_eq!(noise.sample3([x, y, x - y]), 0.);
+                assert_eq!(noise.sample4([x, y, y, x]), 0.);
+            }
+        }
+    }
+
+    #[test]
+    fn bounded_n() {
+        let noise = Perlin::new(13);
+        for i in 0..4096 {
+            let t = i as f32 * 0.0731;
+            let (s, c) = (t.sin() * 9., t.cos() * 7.);
+            assert!(noise.sample1(t).abs() <= 0.5);
+            assert!(noise.sample3([t, s, c]).abs() <= 3f32.sqrt() / 2.);
+            assert!(noise.sample4([t, s, c, -t]).abs() <= 1.);
+        }
+    }
+
+    #[test]
+    fn continuous_n() {
+        let noise = Perlin::new(17);
+        let step = 1e-3;
+        for i in 0..1024 {
+            let t = i as f32 * 0.0173;
+            let p3 = [t, 2. * t, -t];
+            let p4 = [t, -t, 0.5 * t, 3. * t];
+            assert!((noise.sample1(t) - noise.sample1(t + step)).abs() < 1e-2);
+            assert!((noise.sample3(p3) - noise.sample3(p3.map(|p| p + step))).abs() < 1e-2);
+            assert!((noise.sample4(p4) - noise.sample4(p4.map(|p| p + step))).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn f64_matches_f32() {
+        let noise = Perlin::new(19);
+        for i in 0..256 {
+            let point = [i as f32 * 0.37 - 40., i as f32 * 0.71 - 90.];
+            let precise = noise.sample(point.map(f64::from));
+            assert!((precise - noise.sample(point) as f64).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn f64_precise_far_from_origin() {
+        // the lattice hash repeats every 256 cells, 1e6 lands 64 cells into a repeat
+        let noise = Perlin::new(19);
+        for i in 0..2000 {
+            let t = i as f64 * 1e-3;
+            let far = noise.sample([1e6 + t, 1e6 + 0.5 + t / 3.]);
+            let near = noise.sample([64. + t as f32, 64.5 + (t / 3.) as f32]);
+            assert!((far - near as f64).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn f64_continuous_far_from_origin() {
+        let noise = Perlin::new(23);
+        let step = 1e-3;
+        let sample = |i: i32| noise.sample([1e6 + i as f64 * step, -1e6 + 0.25]);
+
+        // crosses two cell boundaries
+        for i in 1..=2000 {
+            let (previous, value) = (sample(i - 1), sample(i));
+            assert_ne!(previous, value);
+            assert!((value - previous).abs() < 1e-2);
+        }
+    }
 
-    let x_weight = px - gx1 as f32;
-    let y_weight = py - gy1 as f32;
+    #[test]
+    fn f32_bands_far_from_origin() {
+        let noise = Perlin::new(23);
+        let distinct = (1..=2000)
+            .map(|i| noise.sample([1e6 + i as f32 * 1e-3, -1e6 + 0.25]))
+            .collect::<Vec<f32>>()
+            .windows(2)
+            .filter(|pair| pair[0] != pair[1])
+            .count();
+        assert!(distinct < 100);
+    }
 
-    // grid bottom left, bottom right, top left, top right
-    let gbl = grid_point_gradient([gx1, gy1], point);
-    let gbr = grid_point_gradient([gx2, gy1], point);
-    let gtl = grid_point_gradient([gx1, gy2], point);
-    let gtr = grid_point_gradient([gx2, gy2], point);
+    #[test]
+    #[cfg(feature = "std")]
+    fn global_rng_untouched() {
+        fastrand::seed(1234);
+        let expected = fastrand::u64(..);
 
-    blerp(gtl, gtr, gbl, gbr, x_weight, y_weight)
+        fastrand::seed(1234);
+        let _ = Perlin::new(99).sample([0.5, 0.5]);
+        let _ = perlin([1.5, 2.5]);
+        assert_eq!(fastrand::u64(..), expected);
+    }
 }
@@ -1,23 +1,151 @@
-use perlin_noise::perlin;
-
-const SAMPLES: usize = 8;
-const DISTANCE: usize = 4;
-fn main() {
-    let mut noise = [[0f32; SAMPLES]; SAMPLES];
-
-    let x_offset = 0.;
-    let y_offset = 0.;
-
-    let delta = DISTANCE as f32 / SAMPLES as f32;
-    for xs in 0..SAMPLES {
-        let x = delta.mul_add(xs as f32, x_offset);
-        for ys in 0..SAMPLES {
-            let y = delta.mul_add(ys as f32, y_offset);
-            noise[ys][xs] = perlin([x, y]);
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, ValueEnum};
+use perlin_noise::{
+    export, Heightmap, HydraulicErosion, Noise2D, Perlin, Simplex, ThermalErosion, Value, White,
+    Worley,
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Algorithm {
+    Perlin,
+    Simplex,
+    Value,
+    Worley,
+    White,
+}
+
+impl Algorithm {
+    fn noise(self, seed: u64) -> Box<dyn Noise2D> {
+        match self {
+            Algorithm::Perlin => Box::new(Perlin::new(seed)),
+            Algorithm::Simplex => Box::new(Simplex::new(seed)),
+            Algorithm::Value => Box::new(Value::new(seed)),
+            Algorithm::Worley => Box::new(Worley::new(seed)),
+            Algorithm::White => Box::new(White::new(seed)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Rows of values as text.
+    Text,
+    /// Binary 8-bit grayscale PGM image.
+    Pgm,
+    /// 8-bit grayscale PNG image.
+    Png,
+    /// Little-endian `f32` values without a header.
+    Raw,
+}
+
+impl Format {
+    fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "txt" => Some(Format::Text),
+            "pgm" => Some(Format::Pgm),
+            "png" => Some(Format::Png),
+            "raw" | "f32" | "bin" => Some(Format::Raw),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    /// Amount of samples along x.
+    #[arg(long, short = 'W', default_value_t = 8)]
+    width: usize,
+
+    /// Amount of samples along y.
+    #[arg(long, short = 'H', default_value_t = 8)]
+    height: usize,
+
+    /// Noise coordinate of the first sample along x.
+    #[arg(long, short = 'x', default_value_t = 0., allow_negative_numbers = true)]
+    x_offset: f32,
+
+    /// Noise coordinate of the first sample along y.
+    #[arg(long, short = 'y', default_value_t = 0., allow_negative_numbers = true)]
+    y_offset: f32,
+
+    /// Noise lattice cells per sample, higher values zoom out.
+    #[arg(long, short, default_value_t = 0.5)]
+    frequency: f32,
+
+    /// Noise algorithm to sample.
+    #[arg(long, short, value_enum, default_value_t = Algorithm::Perlin)]
+    noise: Algorithm,
+
+    /// Seed of the noise field.
+    #[arg(long, short, default_value_t = perlin_noise::DEFAULT_SEED)]
+    seed: u64,
+
+    /// Water droplets of hydraulic erosion run over the sampled heightmap.
+    #[arg(long, default_value_t = 0)]
+    droplets: u32,
+
+    /// Iterations of thermal erosion run after the hydraulic erosion.
+    #[arg(long, default_value_t = 0)]
+    thermal: u32,
+
+    /// Output format, guessed from the output file extension when left out.
+    #[arg(long, short = 't', value_enum)]
+    format: Option<Format>,
+
+    /// File to write to instead of stdout.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+fn write_noise(out: &mut impl Write, format: Format, map: &Heightmap) -> io::Result<()> {
+    let (width, height, noise) = (map.width(), map.height(), map.values());
+    match format {
+        Format::Text => {
+            for row in noise.chunks(width.max(1)) {
+                writeln!(out, "{row:+.4?}")?;
+            }
+            Ok(())
         }
+        Format::Pgm => export::write_pgm(out, width, height, noise),
+        Format::Png => export::write_png(out, width, height, noise),
+        Format::Raw => export::write_raw(out, noise),
     }
+}
+
+fn main() -> io::Result<()> {
+    let cli = Cli::parse();
 
-    for row in noise {
-        println!("{row:+.4?}");
+    let format = cli
+        .format
+        .or_else(|| cli.output.as_deref().and_then(Format::from_extension))
+        .unwrap_or(Format::Text);
+
+    let mut map = Heightmap::from_noise(
+        &cli.noise.noise(cli.seed),
+        [cli.x_offset, cli.y_offset],
+        cli.frequency,
+        cli.width,
+        cli.height,
+    );
+    HydraulicErosion::new(cli.droplets).erode(map.values_mut(), cli.width, cli.height, cli.seed);
+    ThermalErosion::new(cli.thermal).erode(map.values_mut(), cli.width, cli.height);
+
+    match &cli.output {
+        Some(path) => {
+            let mut out = BufWriter::new(File::create(path)?);
+            write_noise(&mut out, format, &map)?;
+            out.flush()
+        }
+        None => {
+            let mut out = io::stdout().lock();
+            write_noise(&mut out, format, &map)?;
+            out.flush()
+        }
     }
 }
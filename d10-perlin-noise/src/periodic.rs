@@ -0,0 +1,125 @@
+use crate::{Noise2D, Perlin};
+
+/// Perlin noise whose lattice wraps every `period` cells, producing a
+/// texture that tiles seamlessly: `sample([x, y]) == sample([x + period, y])`.
+///
+/// Layering it in an [`Fbm`](crate::Fbm) keeps the tiling as long as the
+/// frequency and lacunarity are whole numbers.
+#[derive(Clone, Debug)]
+pub struct Periodic {
+    pub noise: Perlin,
+    pub period: [u32; 2],
+}
+
+impl Periodic {
+    /// # Panics
+    ///
+    /// If either period is zero or above `i32::MAX`.
+    pub fn new(noise: Perlin, period: [u32; 2]) -> Self {
+        assert!(
+            period
+                .iter()
+                .all(|&period| 0 < period && period <= i32::MAX as u32),
+            "noise period must be within 1..=i32::MAX"
+        );
+
+        Self { noise, period }
+    }
+
+    pub fn sample(&self, point: [f32; 2]) -> f32 {
+        self.noise.sample_periodic_n(point, self.period)
+    }
+}
+
+impl Noise2D for Periodic {
+    fn sample(&self, point: [f32; 2]) -> f32 {
+        Periodic::sample(self, point)
+    }
+
+    fn amplitude(&self) -> f32 {
+        self.noise.amplitude()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_points, Fbm};
+
+    const PERIODS: [[u32; 2]; 4] = [[1, 1], [3, 5], [8, 8], [300, 17]];
+
+    /// Random points inside a few periods of the origin.
+    fn points(period: [u32; 2]) -> impl Iterator<Item = [f32; 2]> {
+        let seed = period[0] as u64 * 31 + period[1] as u64;
+        test_points::random(seed, 512, 2.)
+            .map(move |point: [f32; 2]| core::array::from_fn(|i| point[i] * period[i] as f32))
+    }
+
+    #[test]
+    fn wraps_exactly_on_edges() {
+        for period in PERIODS {
+            let noise = Periodic::new(Perlin::new(1), period);
+            let [px, py] = period.map(|p| p as f32);
+            for [x, y] in points(period) {
+                assert_eq!(noise.sample([0., y]), noise.sample([px, y]));
+                assert_eq!(noise.sample([x, 0.]), noise.sample([x, py]));
+            }
+        }
+    }
+
+    #[test]
+    fn repeats_every_period() {
+        for period in PERIODS {
+            let noise = Periodic::new(Perlin::new(2), period);
+            let [px, py] = period.map(|p| p as f32);
+            for [x, y] in points(period) {
+                let value = noise.sample([x, y]);
+                assert!((value - noise.sample([x + px, y])).abs() < 1e-3);
+                assert!((value - noise.sample([x, y + py])).abs() < 1e-3);
+                assert!((value - noise.sample([x - px, y - py])).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn continuous_across_edges() {
+        let step = 1e-3;
+        for period in PERIODS {
+            let noise = Periodic::new(Perlin::new(3), period);
+            let [px, py] = period.map(|p| p as f32);
+            for [x, y] in points(period) {
+                let below_x = noise.sample([px - step, y]);
+                let below_y = noise.sample([x, py - step]);
+                assert!((below_x - noise.sample([0., y])).abs() < 1e-2);
+                assert!((below_y - noise.sample([x, 0.])).abs() < 1e-2);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_perlin_inside_first_period() {
+        let perlin = Perlin::new(4);
+        let noise = Periodic::new(perlin.clone(), [16, 16]);
+        for i in 0..256 {
+            let point = [i as f32 * 0.05, i as f32 * 0.031];
+            assert!((noise.sample(point) - perlin.sample(point)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn fbm_keeps_tiling() {
+        let fbm = Fbm {
+            octaves: 4,
+            ..Fbm::new(Periodic::new(Perlin::new(5), [4, 4]))
+        };
+        for [x, y] in points([4, 4]) {
+            assert!((fbm.sample([x, y]) - fbm.sample([x + 4., y])).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_period_rejected() {
+        Periodic::new(Perlin::new(0), [0, 4]);
+    }
+}
@@ -0,0 +1,116 @@
+/// Random number generator a [`PermutationTable`](crate::PermutationTable)
+/// is shuffled and filled with, see
+/// [`PermutationTable::with_rng`](crate::PermutationTable::with_rng).
+pub trait NoiseRng {
+    fn with_seed(seed: u64) -> Self
+    where
+        Self: Sized;
+
+    /// Uniform value in `[0, 1)`.
+    fn f32(&mut self) -> f32;
+
+    /// Uniform index in `0..=max`.
+    fn index(&mut self, max: usize) -> usize;
+}
+
+/// Generator used by the `new(seed)` constructors. It is the same with and
+/// without `std`, so a seed builds the same world on every target.
+pub type DefaultRng = SplitMix64;
+
+/// `fastrand` as an opt-in generator, with the `fastrand` feature.
+#[cfg(feature = "fastrand")]
+impl NoiseRng for fastrand::Rng {
+    fn with_seed(seed: u64) -> Self {
+        fastrand::Rng::with_seed(seed)
+    }
+
+    fn f32(&mut self) -> f32 {
+        fastrand::Rng::f32(self)
+    }
+
+    fn index(&mut self, max: usize) -> usize {
+        self.usize(..=max)
+    }
+}
+
+/// Small hash based generator built into the crate, needing neither `std`
+/// nor any dependency.
+#[derive(Clone, Debug)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        mix(self.state)
+    }
+}
+
+impl NoiseRng for SplitMix64 {
+    fn with_seed(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn f32(&mut self) -> f32 {
+        // top 24 bits fill the f32 mantissa exactly
+        (self.next_u64() >> 40) as f32 / (1 << 24) as f32
+    }
+
+    fn index(&mut self, max: usize) -> usize {
+        let range = max as u128 + 1;
+        ((self.next_u64() as u128 * range) >> 64) as usize
+    }
+}
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// SplitMix64 finalizer, also usable as a standalone integer hash.
+pub(crate) fn mix(value: u64) -> u64 {
+    let mut z = value;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_mix_reference() {
+        // first outputs for seed 1234567 from the reference implementation
+        let mut rng = SplitMix64::with_seed(1234567);
+        assert_eq!(rng.next_u64(), 6457827717110365317);
+        assert_eq!(rng.next_u64(), 3203168211198807973);
+    }
+
+    #[test]
+    fn split_mix_ranges() {
+        let mut rng = SplitMix64::with_seed(3);
+        for max in 0..300 {
+            assert!(rng.index(max) <= max);
+            assert!((0. ..1.).contains(&rng.f32()));
+        }
+    }
+
+    #[test]
+    fn pluggable_table() {
+        let point = [0.37, 1.91];
+        let a = crate::Perlin::from_table(crate::PermutationTable::with_rng::<SplitMix64>(8));
+        let b = crate::Perlin::from_table(crate::PermutationTable::with_rng::<SplitMix64>(8));
+        assert_eq!(a.sample(point), b.sample(point));
+        assert_eq!(a.seed(), 8);
+    }
+
+    #[test]
+    fn default_is_split_mix() {
+        // the same in every feature set, so seeds give the same worlds
+        let point = [0.37, 1.91];
+        let table = crate::PermutationTable::with_rng::<SplitMix64>(8);
+        assert_eq!(
+            crate::Perlin::new(8).sample(point),
+            crate::Perlin::from_table(table).sample(point)
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use crate::Float;
+use crate::{dot, Noise2D, PermutationTable, DEFAULT_SEED};
+
+/// Squared radius of every simplex corner's influence. Kept at 0.5 so
+/// contributions reach zero before the neighbouring simplex begins.
+const RADIUS_SQUARED: f32 = 0.5;
+
+/// Seeded simplex noise, evaluating `N + 1` corners per sample instead of
+/// the `2^N` a square lattice needs.
+///
+/// Uses the same [`PermutationTable`] as [`Perlin`](crate::Perlin), so a
+/// seed picks the same gradients for both algorithms.
+#[derive(Clone, Debug)]
+pub struct Simplex {
+    table: PermutationTable,
+}
+
+impl Simplex {
+    pub fn new(seed: u64) -> Self {
+        Self::from_table(PermutationTable::new(seed))
+    }
+
+    /// Builds the generator on an existing table, for example one made with
+    /// [`PermutationTable::with_rng`].
+    pub fn from_table(table: PermutationTable) -> Self {
+        Self { table }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.table.seed()
+    }
+
+    pub fn sample(&self, point: [f32; 2]) -> f32 {
+        self.sample_n(point)
+    }
+
+    pub fn sample3(&self, point: [f32; 3]) -> f32 {
+        self.sample_n(point)
+    }
+
+    pub fn sample4(&self, point: [f32; 4]) -> f32 {
+        self.sample_n(point)
+    }
+
+    /// Samples `N` dimensional simplex noise, scaled into roughly `[-1, 1]`.
+    pub fn sample_n<const N: usize>(&self, point: [f32; N]) -> f32 {
+        let n = N as f32;
+        let skew = (Float::sqrt(n + 1.) - 1.) / n;
+        let unskew = (1. - Float::sqrt(n + 1.).recip()) / n;
+
+        // skew into the hypercube lattice to find the containing cell
+        let skewed = point.iter().sum::<f32>() * skew;
+        let cell = point.map(|p| Float::floor(p + skewed) as i32);
+        let unskewed = cell.iter().sum::<i32>() as f32 * unskew;
+        let offset: [f32; N] = core::array::from_fn(|i| point[i] - (cell[i] as f32 - unskewed));
+
+        // walk the simplex corners from the cell origin along the largest
+        // offsets first
+        let mut order: [usize; N] = core::array::from_fn(|i| i);
+        order.sort_unstable_by(|&a, &b| offset[b].total_cmp(&offset[a]));
+
+        let mut corner = [0; N];
+        let mut value = 0.;
+        for step in 0..=N {
+            if step > 0 {
+                corner[order[step - 1]] += 1;
+            }
+
+            let distance: [f32; N] =
+                core::array::from_fn(|i| offset[i] - corner[i] as f32 + step as f32 * unskew);
+            let falloff = RADIUS_SQUARED - dot(distance, distance);
+            if falloff > 0. {
+                let grid_point = core::array::from_fn(|i| cell[i] + corner[i]);
+                let gradient = self.table.gradient(grid_point);
+                let falloff = falloff * falloff;
+                value += falloff * falloff * dot(distance, gradient);
+            }
+        }
+
+        (value * scale(N)).clamp(-1., 1.)
+    }
+}
+
+/// Normalization bringing the peak of unit gradient simplex noise close to
+/// one, measured empirically per dimension.
+fn scale(dimensions: usize) -> f32 {
+    match dimensions {
+        1 => 70.,
+        2 => 99.,
+        _ => 108.,
+    }
+}
+
+impl Noise2D for Simplex {
+    fn sample(&self, point: [f32; 2]) -> f32 {
+        Simplex::sample(self, point)
+    }
+}
+
+impl Default for Simplex {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_points;
+
+    #[test]
+    fn same_seed_same_noise() {
+        let (a, b) = (Simplex::new(9), Simplex::new(9));
+        for [x, y, z, w] in test_points::random(5, 4096, 32.) {
+            assert_eq!(a.sample([x, y]), b.sample([x, y]));
+            assert_eq!(a.sample4([x, y, z, w]), b.sample4([x, y, z, w]));
+        }
+    }
+
+    #[test]
+    fn normalized_range() {
+        let noise = Simplex::new(1);
+        let mut peak = 0f32;
+        for [x, y, z, w] in test_points::random(5, 4096, 32.) {
+            for value in [
+                noise.sample_n([x]),
+                noise.sample([x, y]),
+                noise.sample3([x, y, z]),
+                noise.sample4([x, y, z, w]),
+            ] {
+                assert!((-1. ..=1.).contains(&value));
+                peak = peak.max(value.abs());
+            }
+        }
+        assert!(peak > 0.75);
+    }
+
+    #[test]
+    fn continuous() {
+        let noise = Simplex::new(2);
+        let step = 1e-3;
+        for [x, y, z, w] in test_points::random(5, 4096, 32.) {
+            let p2 = [x, y];
+            let p3 = [x, y, z];
+            let p4 = [x, y, z, w];
+            assert!((noise.sample(p2) - noise.sample(p2.map(|p| p + step))).abs() < 0.05);
+            assert!((noise.sample3(p3) - noise.sample3(p3.map(|p| p + step))).abs() < 0.05);
+            assert!((noise.sample4(p4) - noise.sample4(p4.map(|p| p + step))).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn usable_as_noise_2d() {
+        fn sample(noise: &impl Noise2D) -> f32 {
+            noise.sample([0.3, 0.7])
+        }
+        let noise = Simplex::new(4);
+        assert_eq!(sample(&noise), noise.sample([0.3, 0.7]));
+    }
+}
@@ -0,0 +1,94 @@
+use crate::Float;
+use crate::{rng::DefaultRng, NoiseRng};
+
+const TABLE_SIZE: usize = 256;
+
+/// Seeded permutation and gradient lookup shared by the lattice based noise
+/// generators.
+#[derive(Clone, Debug)]
+pub struct PermutationTable {
+    seed: u64,
+    permutation: [u8; TABLE_SIZE * 2],
+    gradients: [[f32; 2]; TABLE_SIZE],
+}
+
+impl PermutationTable {
+    /// Builds the table with the crate's default generator, see
+    /// [`PermutationTable::with_rng`].
+    pub fn new(seed: u64) -> Self {
+        Self::with_rng::<DefaultRng>(seed)
+    }
+
+    /// Builds the table from a seeded `R`, for plugging in a generator other
+    /// than the default one.
+    pub fn with_rng<R: NoiseRng>(seed: u64) -> Self {
+        let mut rng = R::with_seed(seed);
+
+        let mut table: [u8; TABLE_SIZE] = core::array::from_fn(|i| i as u8);
+        for i in 1..TABLE_SIZE {
+            table.swap(i, rng.index(i));
+        }
+        let permutation = core::array::from_fn(|i| table[i % TABLE_SIZE]);
+
+        let gradients = core::array::from_fn(|_| {
+            let angle = rng.f32() * 2. * core::f32::consts::PI;
+            [Float::cos(angle), Float::sin(angle)]
+        });
+
+        Self {
+            seed,
+            permutation,
+            gradients,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Hashes a lattice point into `0..256`.
+    pub fn hash<const N: usize>(&self, grid_point: [i32; N]) -> usize {
+        grid_point.into_iter().fold(0, |hash, coordinate| {
+            self.permutation[hash + (coordinate & 0xff) as usize] as usize
+        })
+    }
+
+    /// Pseudo-random value in `[-1, 1]` assigned to a lattice point.
+    pub fn value<const N: usize>(&self, grid_point: [i32; N]) -> f32 {
+        Float::mul_add(
+            self.hash(grid_point) as f32 / (TABLE_SIZE - 1) as f32,
+            2.,
+            -1.,
+        )
+    }
+
+    /// Pseudo-random unit gradient assigned to a lattice point.
+    ///
+    /// 1D and 2D gradients come from the seeded angle table, higher
+    /// dimensions pick one of the hypercube edge midpoints like Ken Perlin's
+    /// improved noise does for 3D.
+    pub fn gradient<const N: usize>(&self, grid_point: [i32; N]) -> [f32; N] {
+        let hash = self.hash(grid_point);
+        let mut gradient = [0.; N];
+
+        match N {
+            0 => (),
+            1 | 2 => gradient.copy_from_slice(&self.gradients[hash][..N]),
+            _ => {
+                let (zeroed, signs) = (hash % N, hash / N);
+                let length = Float::sqrt((N - 1) as f32).recip();
+                for (axis, component) in gradient.iter_mut().enumerate() {
+                    if axis != zeroed {
+                        *component = if signs >> axis & 1 == 0 {
+                            length
+                        } else {
+                            -length
+                        };
+                    }
+                }
+            }
+        }
+
+        gradient
+    }
+}
@@ -0,0 +1,18 @@
+//! Sample points shared by the unit tests of the generators.
+
+/// `count` points spread uniformly over `[-range, range)` along every axis,
+/// the same ones for every run with the same `seed`.
+pub(crate) fn random<const N: usize>(
+    seed: u64,
+    count: usize,
+    range: f32,
+) -> impl Iterator<Item = [f32; N]> {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    (0..count).map(move |_| core::array::from_fn(|_| rng.f32() * (2. * range) - range))
+}
+
+/// `count` by `count` points `spacing` apart, starting at the origin.
+pub(crate) fn lattice(count: usize, spacing: [f32; 2]) -> impl Iterator<Item = [f32; 2]> {
+    (0..count)
+        .flat_map(move |x| (0..count).map(move |y| [x as f32 * spacing[0], y as f32 * spacing[1]]))
+}
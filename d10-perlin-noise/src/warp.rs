@@ -0,0 +1,113 @@
+use crate::Float;
+use crate::Noise2D;
+
+/// Offset between the two samples of the warp noise, so the x and y
+/// displacements are not correlated.
+const WARP_OFFSET: [f32; 2] = [5.2, 1.3];
+
+/// Domain warping, displacing the input of `noise` by the output of `warp`.
+///
+/// `sample(p) = noise(p + strength * [warp(p), warp(p + offset)])`
+///
+/// Both fields can be any [`Noise2D`], so an [`Fbm`](crate::Fbm) layer
+/// gives swirling terrain and nesting a `Warp` as the `warp` field warps
+/// the displacement itself for a multi-level warp:
+///
+/// ```
+/// use perlin_noise::{Fbm, Noise2D, Perlin, Warp};
+///
+/// let fbm = Fbm::new(Perlin::new(1));
+/// let marble = Warp::new(Perlin::new(2), Warp::new(fbm.clone(), fbm, 4.), 4.);
+/// let value = marble.sample([0.3, 0.7]);
+/// assert!((-1. ..=1.).contains(&value));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Warp<N, W> {
+    pub noise: N,
+    pub warp: W,
+    /// Displacement applied for a warp value of one.
+    pub strength: f32,
+}
+
+impl<N: Noise2D, W: Noise2D> Warp<N, W> {
+    pub fn new(noise: N, warp: W, strength: f32) -> Self {
+        Self {
+            noise,
+            warp,
+            strength,
+        }
+    }
+
+    /// Point `noise` ends up being sampled at for the input `point`.
+    pub fn displace(&self, point @ [px, py]: [f32; 2]) -> [f32; 2] {
+        let [ox, oy] = WARP_OFFSET;
+        let dx = self.warp.sample(point);
+        let dy = self.warp.sample([px + ox, py + oy]);
+
+        [
+            Float::mul_add(dx, self.strength, px),
+            Float::mul_add(dy, self.strength, py),
+        ]
+    }
+
+    pub fn sample(&self, point: [f32; 2]) -> f32 {
+        self.noise.sample(self.displace(point))
+    }
+}
+
+impl<N: Noise2D, W: Noise2D> Noise2D for Warp<N, W> {
+    fn sample(&self, point: [f32; 2]) -> f32 {
+        Warp::sample(self, point)
+    }
+
+    fn amplitude(&self) -> f32 {
+        self.noise.amplitude()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_points, Fbm, Perlin, Simplex};
+
+    #[test]
+    fn zero_strength_is_identity() {
+        let warp = Warp::new(Perlin::new(1), Simplex::new(2), 0.);
+        for point in test_points::lattice(32, [0.173, 0.291]) {
+            assert_eq!(warp.displace(point), point);
+            assert_eq!(warp.sample(point), warp.noise.sample(point));
+        }
+    }
+
+    #[test]
+    fn displacement_bounded_by_strength() {
+        let warp = Warp::new(Perlin::new(1), Fbm::new(Perlin::new(2)), 3.);
+        for point in test_points::lattice(32, [0.173, 0.291]) {
+            let displaced = warp.displace(point);
+            for axis in 0..2 {
+                assert!((displaced[axis] - point[axis]).abs() <= 3. + 1e-5);
+            }
+        }
+        assert!(test_points::lattice(32, [0.173, 0.291]).any(|point| warp.displace(point) != point));
+    }
+
+    #[test]
+    fn multi_level() {
+        let fbm = Fbm::new(Perlin::new(3));
+        let single = Warp::new(Perlin::new(4), &fbm, 4.);
+        let double = Warp::new(Perlin::new(4), Warp::new(&fbm, &fbm, 4.), 4.);
+
+        assert!(test_points::lattice(32, [0.173, 0.291])
+            .any(|point| single.sample(point) != double.sample(point)));
+        for point in test_points::lattice(32, [0.173, 0.291]) {
+            assert!(double.sample(point).abs() <= double.amplitude());
+        }
+    }
+
+    #[test]
+    fn warped_fbm() {
+        let fbm = Fbm::new(Warp::new(Perlin::new(5), Simplex::new(6), 1.5));
+        assert!(test_points::lattice(32, [0.173, 0.291])
+            .all(|point| (-1. ..=1.).contains(&fbm.sample(point))));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::Float;
+use crate::{rng::mix, Noise2D, DEFAULT_SEED};
+
+/// White noise, an independent random value in `[-1, 1]` for every unit
+/// cell, useful for dithering.
+///
+/// Hashes the full cell coordinates, so unlike the lattice noises it does
+/// not repeat every 256 cells.
+#[derive(Clone, Copy, Debug)]
+pub struct White {
+    seed: u64,
+}
+
+impl White {
+    pub fn new(seed: u64) -> Self {
+        Self { seed: mix(seed) }
+    }
+
+    pub fn sample(&self, #[allow(unused_variables)] point @ [px, py]: [f32; 2]) -> f32 {
+        let [x, y] = [
+            Float::floor(px) as i32 as u32 as u64,
+            Float::floor(py) as i32 as u32 as u64,
+        ];
+        let hash = mix(self.seed ^ (x << 32 | y));
+
+        // top 24 bits fill the f32 mantissa exactly
+        Float::mul_add((hash >> 40) as f32 / (1 << 24) as f32, 2., -1.)
+    }
+}
+
+impl Noise2D for White {
+    fn sample(&self, point: [f32; 2]) -> f32 {
+        White::sample(self, point)
+    }
+}
+
+impl Default for White {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells() -> impl Iterator<Item = [i32; 2]> {
+        (-64..64).flat_map(|x| (-64..64).map(move |y| [x, y]))
+    }
+
+    #[test]
+    fn constant_within_cell() {
+        let noise = White::new(1);
+        for [x, y] in cells().step_by(7) {
+            let (x, y) = (x as f32, y as f32);
+            let value = noise.sample([x, y]);
+            assert_eq!(value, noise.sample([x + 0.5, y + 0.25]));
+            assert_eq!(value, noise.sample([x + 0.999, y + 0.999]));
+        }
+    }
+
+    #[test]
+    fn uniform_distribution() {
+        let noise = White::new(2);
+        let mut buckets = [0; 8];
+        for [x, y] in cells() {
+            let value = noise.sample([x as f32, y as f32]);
+            assert!((-1. ..1.).contains(&value));
+            buckets[((value + 1.) * 4.) as usize] += 1;
+        }
+
+        // 16384 samples, 2048 expected per bucket
+        assert!(buckets.iter().all(|&count| (1800..2300).contains(&count)));
+    }
+
+    #[test]
+    fn seeds_and_far_cells_differ() {
+        let (a, b) = (White::new(1), White::new(2));
+        assert!(
+            cells().any(|[x, y]| a.sample([x as f32, y as f32]) != b.sample([x as f32, y as f32]))
+        );
+        assert_ne!(a.sample([3., 5.]), a.sample([259., 5.]));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::Float;
+use crate::{Noise2D, PermutationTable, DEFAULT_SEED};
+
+/// How the distance to a feature point is measured.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WorleyDistance {
+    #[default]
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+impl WorleyDistance {
+    pub fn measure(self, [dx, dy]: [f32; 2]) -> f32 {
+        match self {
+            Self::Euclidean => Float::hypot(dx, dy),
+            Self::Manhattan => dx.abs() + dy.abs(),
+            Self::Chebyshev => dx.abs().max(dy.abs()),
+        }
+    }
+}
+
+/// Which feature point distance a [`Worley`] sample returns.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WorleyFeature {
+    /// Distance to the nearest point, round cells.
+    #[default]
+    Nearest,
+    /// Distance to the second nearest point.
+    SecondNearest,
+    /// Difference between the two nearest, thin lines along the cell
+    /// borders for cracks and caves.
+    Edge,
+}
+
+/// Worley (cellular) noise, the distance to feature points jittered inside
+/// every lattice cell.
+///
+/// Distances are clamped to `[0, 1]` and mapped onto `[-1, 1]`.
+#[derive(Clone, Debug)]
+pub struct Worley {
+    table: PermutationTable,
+    pub distance: WorleyDistance,
+    pub feature: WorleyFeature,
+}
+
+impl Worley {
+    pub fn new(seed: u64) -> Self {
+        Self::from_table(PermutationTable::new(seed))
+    }
+
+    /// Builds the generator on an existing table, for example one made with
+    /// [`PermutationTable::with_rng`].
+    pub fn from_table(table: PermutationTable) -> Self {
+        Self {
+            table,
+            distance: WorleyDistance::default(),
+            feature: WorleyFeature::default(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.table.seed()
+    }
+
+    /// Feature point inside the cell whose bottom left corner is `cell`.
+    pub fn feature_point(&self, #[allow(unused_variables)] cell @ [x, y]: [i32; 2]) -> [f32; 2] {
+        let jitter = |axis| self.table.hash([x, y, axis]) as f32 / 256.;
+        [x as f32 + jitter(0), y as f32 + jitter(1)]
+    }
+
+    pub fn sample(&self, #[allow(unused_variables)] point @ [px, py]: [f32; 2]) -> f32 {
+        let (cx, cy) = (Float::floor(px) as i32, Float::floor(py) as i32);
+        let (mut nearest, mut second) = (f32::MAX, f32::MAX);
+
+        for y in cy - 1..=cy + 1 {
+            for x in cx - 1..=cx + 1 {
+                let [fx, fy] = self.feature_point([x, y]);
+                let distance = self.distance.measure([fx - px, fy - py]);
+
+                if distance < nearest {
+                    second = nearest;
+                    nearest = distance;
+                } else if distance < second {
+                    second = distance;
+                }
+            }
+        }
+
+        let distance = match self.feature {
+            WorleyFeature::Nearest => nearest,
+            WorleyFeature::SecondNearest => second,
+            WorleyFeature::Edge => second - nearest,
+        };
+
+        Float::mul_add(distance.clamp(0., 1.), 2., -1.)
+    }
+}
+
+impl Noise2D for Worley {
+    fn sample(&self, point: [f32; 2]) -> f32 {
+        Worley::sample(self, point)
+    }
+}
+
+impl Default for Worley {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_points;
+
+    #[test]
+    fn zero_distance_at_feature_points() {
+        let noise = Worley::new(3);
+        for x in -4..4 {
+            for y in -4..4 {
+                let point = noise.feature_point([x, y]);
+                assert_eq!(noise.sample(point), -1.);
+            }
+        }
+    }
+
+    #[test]
+    fn feature_points_inside_cells() {
+        let noise = Worley::new(3);
+        for x in -8..8 {
+            for y in -8..8 {
+                let [fx, fy] = noise.feature_point([x, y]);
+                assert_eq!([fx.floor() as i32, fy.floor() as i32], [x, y]);
+            }
+        }
+    }
+
+    #[test]
+    fn features_ordered() {
+        let with = |feature| Worley {
+            feature,
+            ..Worley::new(8)
+        };
+        let (nearest, second, edge) = (
+            with(WorleyFeature::Nearest),
+            with(WorleyFeature::SecondNearest),
+            with(WorleyFeature::Edge),
+        );
+        for point in test_points::random(13, 2048, 16.) {
+            let (n, s, e) = (
+                nearest.sample(point),
+                second.sample(point),
+                edge.sample(point),
+            );
+            assert!(n <= s);
+            assert!((-1. ..=1.).contains(&e));
+        }
+    }
+
+    #[test]
+    fn distances() {
+        assert_eq!(WorleyDistance::Euclidean.measure([3., -4.]), 5.);
+        assert_eq!(WorleyDistance::Manhattan.measure([3., -4.]), 7.);
+        assert_eq!(WorleyDistance::Chebyshev.measure([3., -4.]), 4.);
+    }
+
+    #[test]
+    fn normalized_range() {
+        for distance in [
+            WorleyDistance::Euclidean,
+            WorleyDistance::Manhattan,
+            WorleyDistance::Chebyshev,
+        ] {
+            let noise = Worley {
+                distance,
+                ..Worley::new(5)
+            };
+            assert!(test_points::random(13, 2048, 16.)
+                .all(|point| (-1. ..=1.).contains(&noise.sample(point))));
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::{fs, io, path::Path};
+
+use crossterm::style::Color;
+
+/// Maps noise values in `[-1, 1]` to terminal colours.
+pub trait ColorMap {
+    fn color(&self, value: f32) -> Color;
+}
+
+impl<T: ColorMap + ?Sized> ColorMap for &T {
+    fn color(&self, value: f32) -> Color {
+        (**self).color(value)
+    }
+}
+
+impl<T: ColorMap + ?Sized> ColorMap for Box<T> {
+    fn color(&self, value: f32) -> Color {
+        (**self).color(value)
+    }
+}
+
+/// Plain grayscale, black at -1 and white at 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Gray;
+
+impl ColorMap for Gray {
+    fn color(&self, value: f32) -> Color {
+        closest_terminal_gray(value)
+    }
+}
+
+pub fn closest_terminal_gray(value: f32) -> Color {
+    let normal = (value + 1.) / 2.;
+    let value = (255. * normal) as u8;
+
+    Color::Rgb {
+        r: value,
+        g: value,
+        b: value,
+    }
+}
+
+/// Colours interpolated between stops placed along `[-1, 1]`.
+///
+/// Two stops at the same position make a hard edge, values outside the
+/// stops take the colour of the nearest one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    stops: Vec<(f32, [u8; 3])>,
+}
+
+impl Gradient {
+    /// # Panics
+    ///
+    /// If `stops` is empty or a position is not a number.
+    pub fn new(mut stops: Vec<(f32, [u8; 3])>) -> Self {
+        assert!(!stops.is_empty(), "gradient needs at least one stop");
+        assert!(
+            stops.iter().all(|(position, _)| !position.is_nan()),
+            "gradient stop positions must be numbers"
+        );
+        // stable, so stops sharing a position keep their order
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Self { stops }
+    }
+
+    /// Deep and shallow water, sand, grass, rock and snow bands.
+    pub fn terrain() -> Self {
+        Self::new(vec![
+            (-1., [10, 42, 107]),
+            (0., [42, 111, 201]),
+            (0., [217, 197, 139]),
+            (0.08, [217, 197, 139]),
+            (0.08, [79, 154, 58]),
+            (0.45, [47, 107, 36]),
+            (0.45, [122, 110, 98]),
+            (0.75, [154, 144, 136]),
+            (0.75, [242, 242, 242]),
+            (1., [255, 255, 255]),
+        ])
+    }
+
+    /// Black through red and yellow to white.
+    pub fn heat() -> Self {
+        Self::new(vec![
+            (-1., [0, 0, 0]),
+            (-0.4, [180, 0, 0]),
+            (0.2, [255, 140, 0]),
+            (0.7, [255, 230, 60]),
+            (1., [255, 255, 255]),
+        ])
+    }
+
+    /// Perceptually uniform dark blue to yellow map from matplotlib.
+    pub fn viridis() -> Self {
+        let colors = [
+            [68, 1, 84],
+            [72, 40, 120],
+            [62, 73, 137],
+            [49, 104, 142],
+            [38, 130, 142],
+            [31, 158, 137],
+            [53, 183, 121],
+            [110, 206, 88],
+            [253, 231, 37],
+        ];
+        let last = (colors.len() - 1) as f32;
+        Self::new(
+            colors
+                .into_iter()
+                .enumerate()
+                .map(|(i, color)| ((i as f32 / last).mul_add(2., -1.), color))
+                .collect(),
+        )
+    }
+
+    /// Parses one `position color` stop per line, colours written as
+    /// `#rrggbb`. Blank lines and lines starting with `#` are skipped.
+    ///
+    /// ```text
+    /// # water to land
+    /// -1   #0a2a6b
+    /// 0    #2a6fc9
+    /// 0    #d9c58b
+    /// 1    #ffffff
+    /// ```
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut stops = vec![];
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |reason: &str| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("gradient line {}: {reason}: {line:?}", number + 1),
+                )
+            };
+
+            let mut fields = line.split_whitespace();
+            let (Some(position), Some(color), None) = (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid("expected a position and a colour"));
+            };
+            let position: f32 = position
+                .parse()
+                .ok()
+                .filter(|position: &f32| position.is_finite())
+                .ok_or_else(|| invalid("position is not a number"))?;
+            let color = parse_hex(color).ok_or_else(|| invalid("colour is not #rrggbb"))?;
+
+            stops.push((position, color));
+        }
+
+        if stops.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "gradient has no stops",
+            ));
+        }
+        Ok(Self::new(stops))
+    }
+
+    /// Reads a gradient file in the [`Gradient::parse`] format.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+}
+
+impl ColorMap for Gradient {
+    fn color(&self, value: f32) -> Color {
+        let after = self
+            .stops
+            .partition_point(|&(position, _)| position <= value);
+        let [r, g, b] = match (self.stops.get(after.wrapping_sub(1)), self.stops.get(after)) {
+            (Some(&(from, low)), Some(&(to, high))) => {
+                let weight = (value - from) / (to - from);
+                std::array::from_fn(|i| {
+                    (weight.mul_add(high[i] as f32 - low[i] as f32, low[i] as f32)).round() as u8
+                })
+            }
+            (Some(&(_, color)), None) | (None, Some(&(_, color))) => color,
+            (None, None) => unreachable!("gradients hold at least one stop"),
+        };
+
+        Color::Rgb { r, g, b }
+    }
+}
+
+fn parse_hex(color: &str) -> Option<[u8; 3]> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok();
+    Some([channel(0)?, channel(1)?, channel(2)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(color: Color) -> [u8; 3] {
+        match color {
+            Color::Rgb { r, g, b } => [r, g, b],
+            other => panic!("expected an rgb colour, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn gray() {
+        let map: Box<dyn ColorMap> = Box::new(Gray);
+        assert_eq!(rgb(map.color(-1.)), [0; 3]);
+        assert_eq!(rgb(map.color(1.)), [255; 3]);
+    }
+
+    #[test]
+    fn gradient_interpolates() {
+        let gradient = Gradient::new(vec![(1., [200, 0, 100]), (-1., [0, 100, 0])]);
+        assert_eq!(rgb(gradient.color(-1.)), [0, 100, 0]);
+        assert_eq!(rgb(gradient.color(0.)), [100, 50, 50]);
+        assert_eq!(rgb(gradient.color(1.)), [200, 0, 100]);
+        assert_eq!(rgb(gradient.color(-3.)), [0, 100, 0]);
+        assert_eq!(rgb(gradient.color(3.)), [200, 0, 100]);
+    }
+
+    #[test]
+    fn shared_positions_make_edges() {
+        let gradient = Gradient::new(vec![
+            (-1., [0; 3]),
+            (0., [0; 3]),
+            (0., [255; 3]),
+            (1., [255; 3]),
+        ]);
+        assert_eq!(rgb(gradient.color(-0.001)), [0; 3]);
+        assert_eq!(rgb(gradient.color(0.)), [255; 3]);
+    }
+
+    #[test]
+    fn named_palettes() {
+        let terrain = Gradient::terrain();
+        assert_eq!(rgb(terrain.color(-0.5))[2], 154);
+        assert_eq!(rgb(terrain.color(0.04)), [217, 197, 139]);
+        assert_eq!(rgb(terrain.color(1.)), [255; 3]);
+
+        assert_eq!(rgb(Gradient::heat().color(-1.)), [0; 3]);
+        assert_eq!(rgb(Gradient::viridis().color(-1.)), [68, 1, 84]);
+        assert_eq!(rgb(Gradient::viridis().color(1.)), [253, 231, 37]);
+    }
+
+    #[test]
+    fn parse_stops() {
+        let gradient = Gradient::parse("# sea\n-1 #000080\n\n  1 #FFff00  \n").unwrap();
+        assert_eq!(
+            gradient,
+            Gradient::new(vec![(-1., [0, 0, 128]), (1., [255, 255, 0])])
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        for (text, message) in [
+            ("", "no stops"),
+            ("0 #000000\nnope #000000", "line 2: position"),
+            ("0 000000", "line 1: colour"),
+            ("0 #00000g", "line 1: colour"),
+            ("0 #000000 extra", "line 1: expected"),
+            ("inf #000000", "line 1: position"),
+        ] {
+            let error = Gradient::parse(text).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert!(error.to_string().contains(message), "{error}");
+        }
+    }
+}
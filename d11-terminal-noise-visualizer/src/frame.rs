@@ -0,0 +1,351 @@
+use std::io;
+
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{
+        Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+    },
+};
+
+/// One terminal cell, a single column wide symbol and its style.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub symbol: char,
+    pub foreground: Color,
+    pub background: Color,
+    /// Swaps the foreground and background, like the status line uses.
+    pub reverse: bool,
+}
+
+impl Cell {
+    /// Space on a `background`.
+    pub fn on(background: Color) -> Self {
+        Self {
+            background,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: ' ',
+            foreground: Color::Reset,
+            background: Color::Reset,
+            reverse: false,
+        }
+    }
+}
+
+/// Row-major grid of cells covering the screen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    /// Frame of blank cells.
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// Cell at column `x` of row `y`, `None` outside the frame.
+    pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
+        (x < self.width && y < self.height)
+            .then(|| &self.cells[y as usize * self.width as usize + x as usize])
+    }
+
+    /// Mutable cell at column `x` of row `y`, `None` outside the frame so
+    /// drawing past the edge is clipped.
+    pub fn get_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
+        (x < self.width && y < self.height)
+            .then(|| &mut self.cells[y as usize * self.width as usize + x as usize])
+    }
+
+    /// Resets every cell to a blank one.
+    pub fn clear(&mut self) {
+        self.cells.fill(Cell::default());
+    }
+
+    /// Writes the frame as lines of text styled by ANSI escapes, for files and
+    /// pipes rather than a terminal screen. Styles are reset at the end of
+    /// every line.
+    pub fn write_ansi(&self, out: &mut impl io::Write) -> io::Result<()> {
+        for row in self.rows() {
+            let mut style = None;
+            for cell in row {
+                queue_style(out, style, cell)?;
+                queue!(out, Print(cell.symbol))?;
+                style = Some(*cell);
+            }
+            queue!(out, ResetColor, Print('\n'))?;
+        }
+        out.flush()
+    }
+
+    /// Writes only the symbols of the frame, one line per row.
+    pub fn write_text(&self, out: &mut impl io::Write) -> io::Result<()> {
+        for row in self.rows() {
+            let line: String = row.iter().map(|cell| cell.symbol).collect();
+            writeln!(out, "{line}")?;
+        }
+        out.flush()
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.chunks_exact(self.width.max(1) as usize)
+    }
+}
+
+/// Sets the attributes and colours of `cell` that differ from the `previous`
+/// cell written, all of them when it is unknown.
+fn queue_style(out: &mut impl io::Write, previous: Option<Cell>, cell: &Cell) -> io::Result<()> {
+    if previous.map(|style| style.reverse) != Some(cell.reverse) {
+        let attribute = if cell.reverse {
+            Attribute::Reverse
+        } else {
+            Attribute::NoReverse
+        };
+        queue!(out, SetAttribute(attribute))?;
+    }
+    if previous.map(|style| style.foreground) != Some(cell.foreground) {
+        queue!(out, SetForegroundColor(cell.foreground))?;
+    }
+    if previous.map(|style| style.background) != Some(cell.background) {
+        queue!(out, SetBackgroundColor(cell.background))?;
+    }
+    Ok(())
+}
+
+/// Double buffered renderer, drawing into a back frame and writing only the
+/// cells that differ from the frame already on screen.
+#[derive(Clone, Debug)]
+pub struct Renderer {
+    back: Frame,
+    /// Frame on screen, `None` when the screen content is unknown.
+    front: Option<Frame>,
+}
+
+impl Renderer {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            back: Frame::new(width, height),
+            front: None,
+        }
+    }
+
+    /// Frame the next [`Renderer::present`] writes.
+    pub fn frame_mut(&mut self) -> &mut Frame {
+        &mut self.back
+    }
+
+    /// Starts over with blank frames of the new size, the next present
+    /// repaints every cell.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.back = Frame::new(width, height);
+        self.invalidate();
+    }
+
+    /// Forgets what is on screen, so the next present repaints every cell.
+    pub fn invalidate(&mut self) {
+        self.front = None;
+    }
+
+    /// Writes the cells of the back frame that changed since the last present,
+    /// moving the cursor only to skip unchanged cells and setting only the
+    /// styles that differ from the previous written cell.
+    ///
+    /// The back frame keeps its content for drawing the next frame on top.
+    pub fn present(&mut self, out: &mut impl io::Write) -> io::Result<()> {
+        let front = self
+            .front
+            .as_ref()
+            .filter(|front| (front.width, front.height) == (self.back.width, self.back.height));
+
+        let mut cursor = None;
+        let mut style: Option<Cell> = None;
+        for y in 0..self.back.height {
+            for x in 0..self.back.width {
+                let cell = self.back.get(x, y).expect("inside the frame");
+                if front.is_some_and(|front| front.get(x, y) == Some(cell)) {
+                    continue;
+                }
+
+                if cursor != Some((x, y)) {
+                    queue!(out, MoveTo(x, y))?;
+                }
+                queue_style(out, style, cell)?;
+                queue!(out, Print(cell.symbol))?;
+
+                style = Some(*cell);
+                // the cursor stays put after writing the last column
+                cursor = (x + 1 < self.back.width).then_some((x + 1, y));
+            }
+        }
+
+        if style.is_some() {
+            queue!(out, ResetColor)?;
+        }
+        out.flush()?;
+
+        match &mut self.front {
+            Some(front) => front.clone_from(&self.back),
+            None => self.front = Some(self.back.clone()),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn present(renderer: &mut Renderer) -> String {
+        let mut out = vec![];
+        renderer.present(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn gray(value: u8) -> Color {
+        Color::Rgb {
+            r: value,
+            g: value,
+            b: value,
+        }
+    }
+
+    fn checker(renderer: &mut Renderer) {
+        let frame = renderer.frame_mut();
+        for y in 0..2 {
+            for x in 0..3 {
+                *frame.get_mut(x, y).unwrap() =
+                    Cell::on(gray(if (x + y) % 2 == 0 { 0 } else { 255 }));
+            }
+        }
+    }
+
+    #[test]
+    fn first_frame_paints_everything() {
+        let mut renderer = Renderer::new(3, 2);
+        checker(&mut renderer);
+
+        assert_eq!(
+            present(&mut renderer),
+            concat!(
+                "\x1b[1;1H\x1b[27m\x1b[39m\x1b[48;2;0;0;0m ",
+                "\x1b[48;2;255;255;255m ",
+                "\x1b[48;2;0;0;0m ",
+                "\x1b[2;1H\x1b[48;2;255;255;255m ",
+                "\x1b[48;2;0;0;0m ",
+                "\x1b[48;2;255;255;255m ",
+                "\x1b[0m",
+            )
+        );
+    }
+
+    #[test]
+    fn unchanged_frame_writes_nothing() {
+        let mut renderer = Renderer::new(3, 2);
+        checker(&mut renderer);
+        present(&mut renderer);
+
+        assert_eq!(present(&mut renderer), "");
+    }
+
+    #[test]
+    fn only_changed_cells() {
+        let mut renderer = Renderer::new(3, 2);
+        checker(&mut renderer);
+        present(&mut renderer);
+
+        let frame = renderer.frame_mut();
+        frame.get_mut(1, 0).unwrap().symbol = '#';
+        *frame.get_mut(2, 0).unwrap() = Cell {
+            symbol: 'x',
+            foreground: gray(9),
+            ..Cell::on(gray(0))
+        };
+        frame.get_mut(1, 1).unwrap().reverse = true;
+
+        assert_eq!(
+            present(&mut renderer),
+            concat!(
+                "\x1b[1;2H\x1b[27m\x1b[39m\x1b[48;2;255;255;255m#",
+                "\x1b[38;2;9;9;9m\x1b[48;2;0;0;0mx",
+                "\x1b[2;2H\x1b[7m\x1b[39m ",
+                "\x1b[0m",
+            )
+        );
+    }
+
+    #[test]
+    fn resize_repaints() {
+        let mut renderer = Renderer::new(3, 2);
+        checker(&mut renderer);
+        present(&mut renderer);
+
+        renderer.resize(2, 1);
+        assert_eq!(
+            present(&mut renderer),
+            "\x1b[1;1H\x1b[27m\x1b[39m\x1b[49m  \x1b[0m"
+        );
+        assert_eq!(present(&mut renderer), "");
+    }
+
+    #[test]
+    fn clipped_access() {
+        let mut frame = Frame::new(2, 2);
+        assert!(frame.get(2, 0).is_none());
+        assert!(frame.get_mut(0, 2).is_none());
+        frame.get_mut(1, 1).unwrap().symbol = 'a';
+        assert_eq!(frame.cells()[3].symbol, 'a');
+        frame.clear();
+        assert_eq!(frame, Frame::new(2, 2));
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut renderer = Renderer::new(3, 2);
+        checker(&mut renderer);
+        let frame = renderer.frame_mut();
+        frame.get_mut(1, 1).unwrap().symbol = '#';
+
+        let mut out = vec![];
+        frame.write_ansi(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            concat!(
+                "\x1b[27m\x1b[39m\x1b[48;2;0;0;0m ",
+                "\x1b[48;2;255;255;255m ",
+                "\x1b[48;2;0;0;0m \x1b[0m\n",
+                "\x1b[27m\x1b[39m\x1b[48;2;255;255;255m ",
+                "\x1b[48;2;0;0;0m#",
+                "\x1b[48;2;255;255;255m \x1b[0m\n",
+            )
+        );
+
+        let mut out = vec![];
+        frame.write_text(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "   \n # \n");
+    }
+}
@@ -1,34 +1,353 @@
-use std::io;
+use perlin_noise::{Noise2D, Perlin, Simplex};
 
-use crossterm::{
-    queue,
-    style::{Color, PrintStyledContent, Stylize},
-};
+mod color;
+mod frame;
+mod quantize;
 
-pub fn closest_terminal_gray(value: f32) -> Color {
-    let normal = (value + 1.) / 2.;
-    let value = (255. * normal) as u8;
+pub use color::{closest_terminal_gray, ColorMap, Gradient, Gray};
+pub use frame::{Cell, Frame, Renderer};
+pub use quantize::{quantize, ColorDepth, Dither};
 
-    Color::Rgb {
-        r: value,
-        g: value,
-        b: value,
+/// How noise samples are laid out in terminal cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderMode {
+    /// One sample per cell, as a coloured space.
+    #[default]
+    Block,
+    /// Two samples per cell, an upper half block coloured by the top sample
+    /// over a background coloured by the bottom one.
+    HalfBlock,
+    /// Two by four monochrome dots per cell from the braille block, for
+    /// high resolution previews.
+    Braille,
+    /// One sample per cell as a character of the ramp in [`RenderOptions`],
+    /// plain text for terminals and logs without colour.
+    Ascii,
+}
+
+impl RenderMode {
+    /// Samples per cell along x and y.
+    pub fn resolution(self) -> (usize, usize) {
+        match self {
+            Self::Block | Self::Ascii => (1, 1),
+            Self::HalfBlock => (1, 2),
+            Self::Braille => (2, 4),
+        }
+    }
+}
+
+/// Default characters of increasing coverage that [`RenderMode::Ascii`] maps
+/// noise values in `[-1, 1]` onto.
+pub const DENSITY_RAMP: &str = " .:-=+*#%@";
+
+/// How [`draw_noise`] turns samples into cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub mode: RenderMode,
+    /// Colours the terminal can show, sample colours are quantized to them.
+    pub depth: ColorDepth,
+    pub dither: Dither,
+    /// Characters from the lowest to the highest value in ASCII mode, each
+    /// a single column wide.
+    pub ramp: Vec<char>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            mode: RenderMode::default(),
+            depth: ColorDepth::default(),
+            dither: Dither::default(),
+            ramp: DENSITY_RAMP.chars().collect(),
+        }
+    }
+}
+
+/// Two dimensional slice at `time` through three dimensional noise, so the
+/// field evolves in place as `time` moves on.
+#[derive(Clone, Debug)]
+pub struct TimeSlice<N> {
+    pub noise: N,
+    pub time: f32,
+}
+
+impl Noise2D for TimeSlice<Perlin> {
+    fn sample(&self, [x, y]: [f32; 2]) -> f32 {
+        self.noise.sample3([x, y, self.time])
+    }
+
+    fn amplitude(&self) -> f32 {
+        // unit gradients reach at most half the diagonal of a cube
+        3f32.sqrt() / 2.
+    }
+}
+
+impl Noise2D for TimeSlice<Simplex> {
+    fn sample(&self, [x, y]: [f32; 2]) -> f32 {
+        self.noise.sample3([x, y, self.time])
+    }
+}
+
+/// Braille character with the dots set in `dots`, indexed `[row][column]`.
+pub fn braille(dots: [[bool; 2]; 4]) -> char {
+    // bit of every dot in the unicode braille block
+    const BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+    let mut code = 0x2800;
+    for (row, bits) in dots.iter().zip(BITS) {
+        for (&dot, bit) in row.iter().zip(bits) {
+            if dot {
+                code |= bit;
+            }
+        }
+    }
+    char::from_u32(code).expect("braille block is valid unicode")
+}
+
+/// 4x4 Bayer matrix, the order in which ordered dithering turns on cells.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Ordered dithering threshold in `(-1, 1)` for the dot at `[x, y]`, spreading
+/// mid grays over neighbouring braille dots instead of a hard cutoff.
+fn dither_threshold([x, y]: [usize; 2]) -> f32 {
+    (BAYER[y % 4][x % 4] as f32 + 0.5) / 8. - 1.
+}
+
+/// Index into a ramp of `len` characters for `value` in `[-1, 1]`, spreading
+/// the range evenly over them.
+fn density_index(value: f32, len: usize) -> usize {
+    let index = ((value + 1.) / 2. * len as f32) as usize;
+    index.min(len - 1)
+}
+
+/// Draws `noise` over the top left `screen` cells of `frame`, sampling it at
+/// `origin` in the top left corner and `scale` cells per noise unit.
+///
+/// Every mode shows the same part of the field, modes with more samples per
+/// cell sample it more densely. Samples are coloured by `colors` and
+/// quantized to the colour depth of `options`, except in the monochrome
+/// braille and ASCII modes.
+///
+/// # Panics
+///
+/// In ASCII mode if the ramp of `options` is empty.
+pub fn draw_noise(
+    frame: &mut Frame,
+    screen: (u16, u16),
+    origin: [f32; 2],
+    scale: f32,
+    options: &RenderOptions,
+    colors: &impl ColorMap,
+    noise: &impl Noise2D,
+) {
+    let (cols, rows) = (screen.0 as usize, screen.1 as usize);
+    let mode = options.mode;
+    assert!(
+        mode != RenderMode::Ascii || !options.ramp.is_empty(),
+        "ASCII mode needs a non-empty ramp"
+    );
+    let (x_samples, y_samples) = mode.resolution();
+    let (width, height) = (cols * x_samples, rows * y_samples);
+    let step = scale.recip() / x_samples as f32;
+    let y_step = scale.recip() / y_samples as f32;
+
+    let mut values = vec![0.; width * height];
+    if x_samples == y_samples {
+        noise.fill_grid(origin, step, width, height, &mut values);
+    } else {
+        // rows are sampled one by one as the vertical step differs
+        for (y, row) in values.chunks_exact_mut(width.max(1)).enumerate() {
+            let row_origin = [origin[0], y_step.mul_add(y as f32, origin[1])];
+            noise.fill_grid(row_origin, step, width, 1, row);
+        }
+    }
+    let sample = |x: usize, y: usize| values[y * width + x];
+
+    let mut colored = match mode {
+        RenderMode::Braille | RenderMode::Ascii => vec![],
+        _ => values.iter().map(|&value| colors.color(value)).collect(),
+    };
+    quantize(&mut colored, width, options.depth, options.dither);
+    let color = |x: usize, y: usize| colored[y * width + x];
+    let ramp = &options.ramp;
+
+    for row in 0..rows {
+        for col in 0..cols {
+            let Some(cell) = frame.get_mut(col as u16, row as u16) else {
+                continue;
+            };
+            *cell = match mode {
+                RenderMode::Block => Cell::on(color(col, row)),
+                RenderMode::HalfBlock => Cell {
+                    symbol: '▀',
+                    foreground: color(col, row * 2),
+                    background: color(col, row * 2 + 1),
+                    reverse: false,
+                },
+                RenderMode::Braille => {
+                    let dots = std::array::from_fn(|dy| {
+                        std::array::from_fn(|dx| {
+                            let [x, y] = [col * 2 + dx, row * 4 + dy];
+                            sample(x, y) > dither_threshold([x, y])
+                        })
+                    });
+                    Cell {
+                        symbol: braille(dots),
+                        ..Cell::default()
+                    }
+                }
+                RenderMode::Ascii => Cell {
+                    symbol: ramp[density_index(sample(col, row), ramp.len())],
+                    ..Cell::default()
+                },
+            };
+        }
     }
 }
 
-pub fn draw_noise(out: &mut impl io::Write, screen: (u16, u16), scale: u8) -> io::Result<()> {
-    let (cols, rows) = screen;
-    let step = 1. / 2f32.powi(scale.max(1) as i32);
+/// Writes `status` in reverse video over the bottom row of `frame`, padded
+/// or cut to its width.
+pub fn draw_status(frame: &mut Frame, status: &str) {
+    let row = frame.height().saturating_sub(1);
+    let symbols = status.chars().chain(std::iter::repeat(' '));
+    for (col, symbol) in (0..frame.width()).zip(symbols) {
+        if let Some(cell) = frame.get_mut(col, row) {
+            *cell = Cell {
+                symbol,
+                reverse: true,
+                ..Cell::default()
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for y in 0..rows {
-        let fy = step * (y as f32);
-        for x in 0..cols {
-            let fx = step * (x as f32);
-            let noise_value = perlin_noise::perlin([fx, fy]);
-            let noise_color = closest_terminal_gray(noise_value);
-            queue!(out, PrintStyledContent(" ".on(noise_color)))?;
+    #[test]
+    fn braille_dots() {
+        assert_eq!(braille([[false; 2]; 4]), '⠀');
+        assert_eq!(braille([[true; 2]; 4]), '⣿');
+        assert_eq!(
+            braille([[true, false], [false, false], [false, false], [false, true]]),
+            '⢁'
+        );
+        assert_eq!(
+            braille([[false, true], [false, false], [true, false], [false, false]]),
+            '⠌'
+        );
+    }
+
+    #[test]
+    fn density_ramp() {
+        let ramp = RenderOptions::default().ramp;
+        let symbol = |value| ramp[density_index(value, ramp.len())];
+        assert_eq!(symbol(-1.), ' ');
+        assert_eq!(symbol(-2.), ' ');
+        assert_eq!(symbol(0.), '+');
+        assert_eq!(symbol(0.99), '@');
+        assert_eq!(symbol(1.), '@');
+    }
+
+    #[test]
+    fn ascii_mode_uses_the_ramp() {
+        let options = RenderOptions {
+            mode: RenderMode::Ascii,
+            ramp: vec!['a', 'b'],
+            ..RenderOptions::default()
+        };
+        let mut frame = Frame::new(16, 4);
+        draw_noise(
+            &mut frame,
+            (16, 4),
+            [0.3, 0.7],
+            4.,
+            &options,
+            &Gray,
+            &Perlin::new(1),
+        );
+
+        let symbols: String = frame.cells().iter().map(|cell| cell.symbol).collect();
+        assert!(symbols.contains('a') && symbols.contains('b'), "{symbols}");
+        assert!(symbols.chars().all(|symbol| symbol == 'a' || symbol == 'b'));
+        assert!(frame
+            .cells()
+            .iter()
+            .all(|cell| cell.background == Cell::default().background));
+    }
+
+    /// Noise counting the grids it fills, to check how draws batch them.
+    struct CountingNoise(std::cell::Cell<usize>);
+
+    impl Noise2D for CountingNoise {
+        fn sample(&self, [x, y]: [f32; 2]) -> f32 {
+            (x - y).sin()
+        }
+
+        fn fill_grid(
+            &self,
+            origin: [f32; 2],
+            step: f32,
+            width: usize,
+            height: usize,
+            out: &mut [f32],
+        ) {
+            assert_eq!(out.len(), width * height);
+            self.0.set(self.0.get() + 1);
+            for (y, row) in out.chunks_exact_mut(width.max(1)).enumerate() {
+                for (x, value) in row.iter_mut().enumerate() {
+                    let point = [
+                        step.mul_add(x as f32, origin[0]),
+                        step.mul_add(y as f32, origin[1]),
+                    ];
+                    *value = self.sample(point);
+                }
+            }
         }
     }
 
-    out.flush()
+    #[test]
+    fn square_samples_fill_one_grid() {
+        for (mode, grids) in [
+            (RenderMode::Block, 1),
+            (RenderMode::Ascii, 1),
+            (RenderMode::HalfBlock, 6),
+            (RenderMode::Braille, 12),
+        ] {
+            let noise = CountingNoise(std::cell::Cell::new(0));
+            let options = RenderOptions {
+                mode,
+                ..RenderOptions::default()
+            };
+            let mut frame = Frame::new(5, 3);
+            draw_noise(&mut frame, (5, 3), [0.5, -1.], 2., &options, &Gray, &noise);
+            assert_eq!(noise.0.get(), grids, "{mode:?}");
+        }
+
+        let noise = CountingNoise(std::cell::Cell::new(0));
+        let mut frame = Frame::new(5, 3);
+        draw_noise(
+            &mut frame,
+            (5, 3),
+            [0.5, -1.],
+            2.,
+            &RenderOptions::default(),
+            &Gray,
+            &noise,
+        );
+        assert_eq!(
+            frame.get(3, 2).unwrap().background,
+            Gray.color(noise.sample([2., 0.]))
+        );
+    }
+
+    #[test]
+    fn dither_thresholds_spread() {
+        let mut thresholds: Vec<f32> = (0..16).map(|i| dither_threshold([i % 4, i / 4])).collect();
+        thresholds.sort_by(f32::total_cmp);
+        assert!(thresholds.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(thresholds[0] > -1. && thresholds[15] < 1.);
+        assert_eq!(dither_threshold([1, 2]), dither_threshold([5, 6]));
+    }
 }
@@ -1,53 +1,735 @@
-use std::io::{self, Write};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use crossterm::{
     cursor::{Hide, Show},
-    event::{read, Event, KeyCode, KeyEvent, KeyModifiers},
+    event::{poll, read, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
     execute, queue,
     terminal::{
-        disable_raw_mode, enable_raw_mode, size, Clear, ClearType, EnterAlternateScreen,
-        LeaveAlternateScreen,
+        disable_raw_mode, enable_raw_mode, size, EnterAlternateScreen, LeaveAlternateScreen,
     },
-    QueueableCommand,
 };
-use terminal_noise_visualizer::draw_noise;
+use perlin_noise::{
+    Fbm, FractalKind, Noise2D, NoiseRng, Perlin, Simplex, SplitMix64, Value, White, Worley,
+};
+use terminal_noise_visualizer::{
+    draw_noise, draw_status, ColorDepth, ColorMap, Dither, Frame, Gradient, Gray, RenderMode,
+    RenderOptions, Renderer, TimeSlice, DENSITY_RAMP,
+};
+use unicode_width::UnicodeWidthChar;
+
+/// Cells moved by one pan key press.
+const PAN_CELLS: f32 = 4.;
+/// Scale multiplier of one zoom key press.
+const ZOOM_FACTOR: f32 = 1.25;
+const MAX_OCTAVES: u32 = 12;
+/// Scale limits, far enough inside the noise lattice's `i32` range that the
+/// whole screen stays sampleable.
+const MIN_SCALE: f32 = 1e-3;
+const MAX_SCALE: f32 = 1e4;
+/// Largest distance of the origin from zero in noise units.
+const MAX_ORIGIN: f32 = 1e7;
+/// Animation speed multiplier of one speed key press.
+const SPEED_FACTOR: f32 = 1.25;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Algorithm {
+    Perlin,
+    Simplex,
+    Value,
+    Worley,
+    White,
+}
+
+impl Algorithm {
+    /// Noise of `seed`, sliced at `time` through the third dimension for the
+    /// algorithms that have one.
+    fn noise(self, seed: u64, time: Option<f32>) -> Box<dyn Noise2D> {
+        match (self, time) {
+            (Algorithm::Perlin, Some(time)) => Box::new(TimeSlice {
+                noise: Perlin::new(seed),
+                time,
+            }),
+            (Algorithm::Simplex, Some(time)) => Box::new(TimeSlice {
+                noise: Simplex::new(seed),
+                time,
+            }),
+            (Algorithm::Perlin, None) => Box::new(Perlin::new(seed)),
+            (Algorithm::Simplex, None) => Box::new(Simplex::new(seed)),
+            (Algorithm::Value, _) => Box::new(Value::new(seed)),
+            (Algorithm::Worley, _) => Box::new(Worley::new(seed)),
+            (Algorithm::White, _) => Box::new(White::new(seed)),
+        }
+    }
+
+    fn has_time(self) -> bool {
+        matches!(self, Algorithm::Perlin | Algorithm::Simplex)
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Fractal {
+    Standard,
+    Ridged,
+    Turbulence,
+}
+
+impl From<Fractal> for FractalKind {
+    fn from(fractal: Fractal) -> Self {
+        match fractal {
+            Fractal::Standard => FractalKind::Standard,
+            Fractal::Ridged => FractalKind::Ridged,
+            Fractal::Turbulence => FractalKind::Turbulence,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Mode {
+    /// One sample per cell.
+    Block,
+    /// Two samples per cell using upper half blocks.
+    HalfBlock,
+    /// Two by four monochrome braille dots per cell.
+    Braille,
+    /// One character of the ramp per cell, without colours.
+    Ascii,
+}
+
+impl From<Mode> for RenderMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Block => RenderMode::Block,
+            Mode::HalfBlock => RenderMode::HalfBlock,
+            Mode::Braille => RenderMode::Braille,
+            Mode::Ascii => RenderMode::Ascii,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Palette {
+    Gray,
+    /// Water, sand, grass, rock and snow bands.
+    Terrain,
+    Heat,
+    Viridis,
+}
+
+impl Palette {
+    fn colors(self) -> Box<dyn ColorMap> {
+        match self {
+            Palette::Gray => Box::new(Gray),
+            Palette::Terrain => Box::new(Gradient::terrain()),
+            Palette::Heat => Box::new(Gradient::heat()),
+            Palette::Viridis => Box::new(Gradient::viridis()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Colors {
+    /// Detected from COLORTERM and TERM, true colour in snapshots.
+    Auto,
+    #[value(name = "truecolor")]
+    TrueColor,
+    #[value(name = "256")]
+    Ansi256,
+    #[value(name = "16")]
+    Ansi16,
+}
+
+impl Colors {
+    fn depth(self) -> ColorDepth {
+        match self {
+            Colors::Auto => ColorDepth::detect(),
+            Colors::TrueColor => ColorDepth::TrueColor,
+            Colors::Ansi256 => ColorDepth::Ansi256,
+            Colors::Ansi16 => ColorDepth::Ansi16,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Dithering {
+    None,
+    /// Bayer matrix cross-hatch.
+    Ordered,
+    /// Error diffusion.
+    FloydSteinberg,
+}
+
+impl From<Dithering> for Dither {
+    fn from(dithering: Dithering) -> Self {
+        match dithering {
+            Dithering::None => Dither::None,
+            Dithering::Ordered => Dither::Ordered,
+            Dithering::FloydSteinberg => Dither::FloydSteinberg,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SnapshotFormat {
+    /// Coloured text with ANSI escapes.
+    Ansi,
+    /// Plain density characters.
+    Ascii,
+}
+
+/// Snapshot size in cells, written `WxH`.
+#[derive(Clone, Copy, Debug)]
+struct Size {
+    width: u16,
+    height: u16,
+}
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected WIDTHxHEIGHT, got {text:?}");
+        let (width, height) = text.split_once(['x', 'X']).ok_or_else(invalid)?;
+        Ok(Self {
+            width: width.parse().map_err(|_| invalid())?,
+            height: height.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Accepts ramps of printable characters one column wide, anything else
+/// would move the cursor away from the cell the renderer tracks.
+fn parse_ramp(text: &str) -> Result<String, String> {
+    if text.is_empty() {
+        return Err("the ramp needs at least one character".into());
+    }
+    if let Some(symbol) = text
+        .chars()
+        .find(|&symbol| symbol.is_control() || symbol.width() != Some(1))
+    {
+        return Err(format!(
+            "ramp characters must be printable and one column wide, got {symbol:?}"
+        ));
+    }
+    Ok(text.into())
+}
 
 #[derive(Parser)]
-struct CLI {
-    /// Log2 value for scaling the perlin noise coordinate stepping. Higher values zoom in. Default 1, min 1.
+struct Cli {
+    /// Log2 value for scaling the perlin noise coordinate stepping at startup, +/- zoom from there. Higher values zoom in. Default 1, min 1.
     #[arg(long, short, default_value_t = 1)]
     pub scale_log2: u8,
+
+    /// Noise algorithm to visualize.
+    #[arg(long, short, value_enum, default_value_t = Algorithm::Perlin)]
+    pub algorithm: Algorithm,
+
+    /// Amount of noise octaves layered on top of each other. Default 1.
+    #[arg(long, short, default_value_t = 1)]
+    pub octaves: u32,
+
+    /// How octaves are combined.
+    #[arg(long, short, value_enum, default_value_t = Fractal::Standard)]
+    pub fractal: Fractal,
+
+    /// Seed of the noise field.
+    #[arg(long, short = 'e', default_value_t = perlin_noise::DEFAULT_SEED)]
+    pub seed: u64,
+
+    /// How samples are drawn into terminal cells.
+    #[arg(long, short, value_enum, default_value_t = Mode::Block)]
+    pub mode: Mode,
+
+    /// Colour palette of the noise values.
+    #[arg(long, short, value_enum, default_value_t = Palette::Gray)]
+    pub palette: Palette,
+
+    /// File of `position #rrggbb` gradient stops along [-1, 1], one per line, used instead of the palette.
+    #[arg(long, short)]
+    pub gradient: Option<PathBuf>,
+
+    /// Characters from the lowest to the highest noise value in ASCII mode.
+    #[arg(long, default_value = DENSITY_RAMP, value_parser = parse_ramp)]
+    pub ramp: String,
+
+    /// Colours the terminal can show, lower depths are quantized to.
+    #[arg(long, value_enum, default_value_t = Colors::Auto)]
+    pub colors: Colors,
+
+    /// How quantizing to 256 or 16 colours spreads the error.
+    #[arg(long, value_enum, default_value_t = Dithering::None)]
+    pub dither: Dithering,
+
+    /// Animate the noise, evolving Perlin and simplex through time and scrolling the others.
+    #[arg(long)]
+    pub animate: bool,
+
+    /// Target frames per second of the animation.
+    #[arg(long, default_value_t = 30.)]
+    pub fps: f32,
+
+    /// Noise units the animation moves per second.
+    #[arg(long, default_value_t = 0.5, allow_negative_numbers = true)]
+    pub speed: f32,
+
+    /// Write a single frame of WxH cells and exit, leaving the terminal alone.
+    #[arg(long, value_name = "WxH")]
+    pub snapshot: Option<Size>,
+
+    /// How the snapshot is written.
+    #[arg(long, value_enum, default_value_t = SnapshotFormat::Ansi)]
+    pub format: SnapshotFormat,
+
+    /// File the snapshot is written to instead of stdout.
+    #[arg(long, requires = "snapshot")]
+    pub output: Option<PathBuf>,
+}
+
+/// Part of the noise field on screen, changed by the key controls.
+struct View {
+    origin: [f32; 2],
+    /// Terminal cells per noise unit.
+    scale: f32,
+    seed: u64,
+    octaves: u32,
+    /// Animation clock in noise units, `None` for a still image.
+    time: Option<f32>,
+    speed: f32,
+    paused: bool,
+    /// Time the last noise frame took to render.
+    frame_time: Duration,
+}
+
+impl View {
+    /// View at the origin with the settings of `cli`.
+    fn new(cli: &Cli) -> Self {
+        Self {
+            origin: [0., 0.],
+            scale: 2f32.powi(cli.scale_log2.max(1) as i32),
+            seed: cli.seed,
+            octaves: cli.octaves,
+            time: cli.animate.then_some(0.),
+            speed: cli.speed,
+            paused: false,
+            frame_time: Duration::ZERO,
+        }
+    }
+
+    fn noise(&self, cli: &Cli) -> Fbm<Box<dyn Noise2D>> {
+        Fbm {
+            octaves: self.octaves,
+            kind: cli.fractal.into(),
+            ..Fbm::new(cli.algorithm.noise(self.seed, self.time))
+        }
+    }
+
+    /// Top left corner on screen, scrolled along x by the animation clock
+    /// when the algorithm has no time dimension.
+    fn scrolled_origin(&self, cli: &Cli) -> [f32; 2] {
+        match self.time {
+            Some(time) if !cli.algorithm.has_time() => [
+                (self.origin[0] + time).clamp(-MAX_ORIGIN, MAX_ORIGIN),
+                self.origin[1],
+            ],
+            _ => self.origin,
+        }
+    }
+
+    /// Moves the animation clock on by `elapsed` time.
+    fn advance(&mut self, elapsed: Duration) {
+        if let Some(time) = &mut self.time {
+            *time += self.speed * elapsed.as_secs_f32();
+        }
+    }
+
+    /// Applies a key press, returning whether the screen needs a redraw.
+    fn press(
+        &mut self,
+        code: KeyCode,
+        screen: (u16, u16),
+        frame: Duration,
+        seeds: &mut SplitMix64,
+    ) -> bool {
+        match code {
+            KeyCode::Left | KeyCode::Char('h') => self.pan([-1., 0.]),
+            KeyCode::Right | KeyCode::Char('l') => self.pan([1., 0.]),
+            KeyCode::Up | KeyCode::Char('k') => self.pan([0., -1.]),
+            KeyCode::Down | KeyCode::Char('j') => self.pan([0., 1.]),
+            KeyCode::Char('+' | '=') => self.zoom(screen, ZOOM_FACTOR),
+            KeyCode::Char('-' | '_') => self.zoom(screen, ZOOM_FACTOR.recip()),
+            KeyCode::Char('r') => self.seed = seeds.next_u64(),
+            KeyCode::Char('o') => self.octaves = self.octaves.saturating_sub(1).max(1),
+            KeyCode::Char('p') => self.octaves = (self.octaves + 1).min(MAX_OCTAVES),
+            KeyCode::Char(' ') if self.time.is_some() => self.paused = !self.paused,
+            KeyCode::Char('.') if self.time.is_some() => {
+                self.paused = true;
+                self.advance(frame);
+            }
+            KeyCode::Char('[') if self.time.is_some() => self.speed /= SPEED_FACTOR,
+            KeyCode::Char(']') if self.time.is_some() => self.speed *= SPEED_FACTOR,
+            _ => return false,
+        }
+        true
+    }
+
+    fn pan(&mut self, [dx, dy]: [f32; 2]) {
+        self.origin[0] += dx * PAN_CELLS / self.scale;
+        self.origin[1] += dy * PAN_CELLS / self.scale;
+        self.clamp_origin();
+    }
+
+    /// Scales by `factor` keeping the noise under the centre of `screen` in
+    /// place, up to the scale limits.
+    fn zoom(&mut self, screen: (u16, u16), factor: f32) {
+        let scale = (self.scale * factor).clamp(MIN_SCALE, MAX_SCALE);
+        let centre = [screen.0 as f32 / 2., screen.1 as f32 / 2.];
+        for (origin, centre) in self.origin.iter_mut().zip(centre) {
+            *origin += centre / self.scale - centre / scale;
+        }
+        self.scale = scale;
+        self.clamp_origin();
+    }
+
+    fn clamp_origin(&mut self) {
+        for origin in &mut self.origin {
+            *origin = origin.clamp(-MAX_ORIGIN, MAX_ORIGIN);
+        }
+    }
+
+    fn status(&self) -> String {
+        let mut status = format!(
+            " x {:.2}  y {:.2}  scale {:.2}  seed {}  octaves {}",
+            self.origin[0], self.origin[1], self.scale, self.seed, self.octaves
+        );
+        if let Some(time) = self.time {
+            status += &format!(
+                "  t {time:.2}  speed {:.2}  frame {:.1} ms{}",
+                self.speed,
+                self.frame_time.as_secs_f32() * 1000.,
+                if self.paused { "  paused" } else { "" }
+            );
+        }
+        status += "  |  arrows/hjkl pan  +/- zoom  r reseed  o/p octaves";
+        if self.time.is_some() {
+            status += "  space pause  . step  [/] speed";
+        }
+        status + "  q quit"
+    }
+}
+
+/// Paces animation frames by wall time, independent of how many events
+/// arrive between them.
+struct Clock {
+    frame: Duration,
+    next_frame: Instant,
+    last_tick: Instant,
+}
+
+impl Clock {
+    fn new(frame: Duration, now: Instant) -> Self {
+        Self {
+            frame,
+            next_frame: now + frame,
+            last_tick: now,
+        }
+    }
+
+    /// Time left until the next frame is due.
+    fn timeout(&self, now: Instant) -> Duration {
+        self.next_frame.saturating_duration_since(now)
+    }
+
+    /// Time passed since the last frame when the next one is due at `now`.
+    fn tick(&mut self, now: Instant) -> Option<Duration> {
+        if now < self.next_frame {
+            return None;
+        }
+        // skip frames the terminal could not keep up with
+        self.next_frame += self.frame;
+        if self.next_frame <= now {
+            self.next_frame = now + self.frame;
+        }
+        let elapsed = now - self.last_tick;
+        self.last_tick = now;
+        Some(elapsed)
+    }
+}
+
+/// How the noise is drawn with the settings of `cli`.
+fn render_options(cli: &Cli) -> RenderOptions {
+    let depth = match (cli.colors, cli.snapshot) {
+        // snapshots are committed and diffed, they must not depend on the
+        // terminal that happened to write them
+        (Colors::Auto, Some(_)) => ColorDepth::TrueColor,
+        (colors, _) => colors.depth(),
+    };
+    RenderOptions {
+        mode: cli.mode.into(),
+        depth,
+        dither: cli.dither.into(),
+        ramp: cli.ramp.chars().collect(),
+    }
+}
+
+/// Redraws the noise over all rows but the last, which holds the status,
+/// writing only the cells that changed.
+fn draw(
+    out: &mut impl Write,
+    renderer: &mut Renderer,
+    cli: &Cli,
+    view: &mut View,
+    options: &RenderOptions,
+    colors: &impl ColorMap,
+) -> io::Result<()> {
+    let start = Instant::now();
+    let frame = renderer.frame_mut();
+    let screen = (frame.width(), frame.height().saturating_sub(1));
+    draw_noise(
+        frame,
+        screen,
+        view.scrolled_origin(cli),
+        view.scale,
+        options,
+        colors,
+        &view.noise(cli),
+    );
+    // presented before the status so the readout covers writing the noise
+    renderer.present(out)?;
+    view.frame_time = start.elapsed();
+
+    draw_status(renderer.frame_mut(), &view.status());
+    renderer.present(out)
+}
+
+/// Draws one frame of `size` cells and writes it as text, without the
+/// status line or any cursor movement.
+fn snapshot(
+    out: &mut impl Write,
+    size: Size,
+    cli: &Cli,
+    view: &View,
+    options: &RenderOptions,
+    colors: &impl ColorMap,
+) -> io::Result<()> {
+    let mut options = options.clone();
+    if let SnapshotFormat::Ascii = cli.format {
+        options.mode = RenderMode::Ascii;
+    }
+    let mut frame = Frame::new(size.width, size.height);
+    draw_noise(
+        &mut frame,
+        (size.width, size.height),
+        view.scrolled_origin(cli),
+        view.scale,
+        &options,
+        colors,
+        &view.noise(cli),
+    );
+
+    match cli.format {
+        SnapshotFormat::Ansi => frame.write_ansi(out),
+        SnapshotFormat::Ascii => frame.write_text(out),
+    }
 }
 
 fn main() -> io::Result<()> {
-    let cli = CLI::parse();
+    let cli = Cli::parse();
+    let mut view = View::new(&cli);
+    let colors: Box<dyn ColorMap> = match &cli.gradient {
+        Some(path) => Box::new(Gradient::load(path)?),
+        None => cli.palette.colors(),
+    };
+    let options = render_options(&cli);
+
+    if let Some(size) = cli.snapshot {
+        let mut out: Box<dyn Write> = match &cli.output {
+            Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+            None => Box::new(io::stdout().lock()),
+        };
+        return snapshot(&mut out, size, &cli, &view, &options, &colors);
+    }
+
     let mut out = io::stdout();
-    let screen = size()?;
+    let mut screen = size()?;
+    let mut seeds = SplitMix64::with_seed(cli.seed);
+    let frame = Duration::from_secs_f32(cli.fps.max(1.).recip());
+    let mut clock = Clock::new(frame, Instant::now());
+    let mut renderer = Renderer::new(screen.0, screen.1);
 
     enable_raw_mode()?;
     queue!(out, Hide, EnterAlternateScreen)?;
 
-    draw_noise(&mut out, screen, cli.scale_log2)?;
-    out.flush()?;
+    draw(&mut out, &mut renderer, &cli, &mut view, &options, &colors)?;
 
     loop {
-        match read()? {
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('c'),
-                modifiers: KeyModifiers::CONTROL,
+        // animations wait for input only until the next frame is due
+        let event = if view.time.is_some() {
+            poll(clock.timeout(Instant::now()))?
+                .then(read)
+                .transpose()?
+        } else {
+            Some(read()?)
+        };
+
+        let mut redraw = match event {
+            None => false,
+            Some(
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char('c'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                })
+                | Event::Key(KeyEvent {
+                    code: KeyCode::Char('q') | KeyCode::Esc,
+                    ..
+                }),
+            ) => break,
+            Some(Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
                 ..
-            }) => break,
-            Event::Resize(cols, rows) => {
-                let screen = (cols, rows);
-                out.queue(Clear(ClearType::All))?;
-                draw_noise(&mut out, screen, cli.scale_log2)?;
-                out.flush()?;
+            })) => view.press(code, screen, frame, &mut seeds),
+            Some(Event::Resize(cols, rows)) => {
+                screen = (cols, rows);
+                renderer.resize(cols, rows);
+                true
             }
-            _ => (),
+            Some(_) => false,
         };
+
+        // checked after every event, a steady stream of them must not stall
+        // the animation
+        if view.time.is_some() {
+            if let Some(elapsed) = clock.tick(Instant::now()) {
+                if !view.paused {
+                    view.advance(elapsed);
+                    redraw = true;
+                }
+            }
+        }
+
+        if redraw {
+            draw(&mut out, &mut renderer, &cli, &mut view, &options, &colors)?;
+        }
     }
 
     execute!(out, LeaveAlternateScreen, Show)?;
     disable_raw_mode()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_snapshot(args: &[&str]) -> String {
+        let cli = Cli::try_parse_from(["terminal-noise-visualizer"].iter().chain(args)).unwrap();
+        let view = View::new(&cli);
+        let colors = cli.palette.colors();
+        let mut out = vec![];
+        snapshot(
+            &mut out,
+            cli.snapshot.unwrap(),
+            &cli,
+            &view,
+            &render_options(&cli),
+            &colors,
+        )
+        .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn ramp_characters() {
+        assert_eq!(parse_ramp(DENSITY_RAMP).unwrap(), DENSITY_RAMP);
+        assert!(parse_ramp("░▒▓█").is_ok());
+        for ramp in ["", " .\t#", "ab\n", "\x1b[", ".界", " 🙂", "a\u{301}"] {
+            assert!(parse_ramp(ramp).is_err(), "{ramp:?}");
+        }
+    }
+
+    #[test]
+    fn zoom_and_pan_stay_sampleable() {
+        let cli = Cli::try_parse_from(["terminal-noise-visualizer"]).unwrap();
+        let mut view = View::new(&cli);
+        let screen = (200, 60);
+        for _ in 0..200 {
+            view.zoom(screen, ZOOM_FACTOR.recip());
+            view.pan([1., 1.]);
+        }
+        assert_eq!(view.scale, MIN_SCALE);
+        assert!(view.origin.iter().all(|origin| origin.abs() <= MAX_ORIGIN));
+
+        let mut frame = Frame::new(screen.0, screen.1);
+        let options = render_options(&cli);
+        draw_noise(
+            &mut frame,
+            screen,
+            view.origin,
+            view.scale,
+            &options,
+            &Gray,
+            &view.noise(&cli),
+        );
+
+        for _ in 0..1000 {
+            view.zoom(screen, ZOOM_FACTOR);
+        }
+        assert_eq!(view.scale, MAX_SCALE);
+        view.zoom(screen, ZOOM_FACTOR.recip());
+        assert!(view.scale < MAX_SCALE);
+    }
+
+    #[test]
+    fn clock_follows_wall_time() {
+        let frame = Duration::from_millis(10);
+        let start = Instant::now();
+        let mut clock = Clock::new(frame, start);
+
+        assert_eq!(clock.tick(start + Duration::from_millis(4)), None);
+        assert_eq!(
+            clock.timeout(start + Duration::from_millis(4)),
+            Duration::from_millis(6)
+        );
+        assert_eq!(clock.tick(start + Duration::from_millis(10)), Some(frame));
+
+        // frames missed while busy are caught up in one longer step
+        let late = start + Duration::from_millis(45);
+        assert_eq!(clock.tick(late), Some(Duration::from_millis(35)));
+        assert_eq!(clock.timeout(late), frame);
+        assert_eq!(clock.tick(late + Duration::from_millis(3)), None);
+        assert_eq!(clock.tick(late + frame), Some(frame));
+    }
+
+    #[test]
+    fn ascii_snapshot() {
+        let text = run_snapshot(&["--snapshot", "12x3", "--format", "ascii", "-s", "3"]);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.chars().count() == 12));
+        assert!(text
+            .chars()
+            .all(|symbol| symbol == '\n' || DENSITY_RAMP.contains(symbol)));
+        assert_eq!(
+            text,
+            run_snapshot(&["--snapshot", "12x3", "--format", "ascii", "-s", "3"])
+        );
+    }
+
+    #[test]
+    fn ansi_snapshot_ignores_the_terminal() {
+        let text = run_snapshot(&["--snapshot", "4x2"]);
+        assert_eq!(text.lines().count(), 2);
+        assert!(text
+            .lines()
+            .all(|line| line.contains("\x1b[48;2;") && line.ends_with("\x1b[0m")));
+
+        let text = run_snapshot(&["--snapshot", "4x2", "--colors", "16"]);
+        assert!(!text.contains("\x1b[48;2;"));
+    }
+}
@@ -0,0 +1,306 @@
+use crossterm::style::Color;
+
+use crate::BAYER;
+
+/// Colours a terminal can show.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit RGB.
+    #[default]
+    TrueColor,
+    /// The xterm 256 colour palette, its 6x6x6 cube and 24 step gray ramp.
+    Ansi256,
+    /// The 16 basic ANSI colours.
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Reads the capability of the current terminal from the environment,
+    /// see [`ColorDepth::from_env`].
+    pub fn detect() -> Self {
+        let var = |name| std::env::var(name).ok();
+        Self::from_env(var("COLORTERM").as_deref(), var("TERM").as_deref())
+    }
+
+    /// Picks the depth advertised by `COLORTERM` and `TERM`, falling back to
+    /// 16 colours when neither says more.
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if matches!(colorterm, Some("truecolor" | "24bit"))
+            || term.is_some_and(|term| term.ends_with("-direct"))
+        {
+            Self::TrueColor
+        } else if term.is_some_and(|term| term.contains("256color")) {
+            Self::Ansi256
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// Closest colour of this depth, leaving colours other than RGB as they
+    /// are.
+    pub fn quantize(self, color: Color) -> Color {
+        match color {
+            Color::Rgb { r, g, b } => self.nearest([r, g, b].map(f32::from)).0,
+            color => color,
+        }
+    }
+
+    /// Closest colour to `rgb` along with the RGB value it shows as.
+    fn nearest(self, rgb: [f32; 3]) -> (Color, [f32; 3]) {
+        let rgb = rgb.map(|channel| channel.clamp(0., 255.));
+        match self {
+            Self::TrueColor => {
+                let [r, g, b] = rgb.map(|channel| channel.round() as u8);
+                (Color::Rgb { r, g, b }, rgb)
+            }
+            Self::Ansi256 => {
+                let cube = rgb.map(|channel| {
+                    (0..6)
+                        .min_by_key(|&level| (CUBE_LEVELS[level] as f32 - channel).abs() as u32)
+                        .unwrap()
+                });
+                let cube_rgb = cube.map(|level| CUBE_LEVELS[level] as f32);
+
+                let mean = rgb.iter().sum::<f32>() / 3.;
+                let gray = ((mean - 8.) / 10.).round().clamp(0., 23.);
+                let gray_rgb = [gray.mul_add(10., 8.); 3];
+
+                if distance(rgb, gray_rgb) < distance(rgb, cube_rgb) {
+                    (Color::AnsiValue(232 + gray as u8), gray_rgb)
+                } else {
+                    let index = 16 + 36 * cube[0] + 6 * cube[1] + cube[2];
+                    (Color::AnsiValue(index as u8), cube_rgb)
+                }
+            }
+            Self::Ansi16 => ANSI16
+                .iter()
+                .map(|&(color, value)| (color, value.map(f32::from)))
+                .min_by(|a, b| distance(rgb, a.1).total_cmp(&distance(rgb, b.1)))
+                .unwrap(),
+        }
+    }
+}
+
+impl ColorDepth {
+    /// Distance to the neighbouring palette entries around one showing as
+    /// `rgb`, the range ordered dithering spreads a colour over.
+    fn step(self, rgb: [f32; 3]) -> f32 {
+        match self {
+            Self::TrueColor => 1.,
+            // grays sit on the 10 step ramp, the cube levels are 40 apart
+            Self::Ansi256 if rgb[0] == rgb[1] && rgb[1] == rgb[2] => 10.,
+            Self::Ansi256 => 40.,
+            Self::Ansi16 => 128.,
+        }
+    }
+}
+
+/// How the error of quantizing to a lower [`ColorDepth`] is spread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dither {
+    /// Every colour snaps to its closest one, banding smooth gradients.
+    #[default]
+    None,
+    /// Thresholds nudged by a 4x4 Bayer matrix, a regular cross-hatch.
+    Ordered,
+    /// Floyd–Steinberg error diffusion onto the following samples.
+    FloydSteinberg,
+}
+
+/// Channel values of the xterm 6x6x6 colour cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The 16 ANSI colours with xterm's default RGB values.
+const ANSI16: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::DarkRed, [205, 0, 0]),
+    (Color::DarkGreen, [0, 205, 0]),
+    (Color::DarkYellow, [205, 205, 0]),
+    (Color::DarkBlue, [0, 0, 238]),
+    (Color::DarkMagenta, [205, 0, 205]),
+    (Color::DarkCyan, [0, 205, 205]),
+    (Color::Grey, [229, 229, 229]),
+    (Color::DarkGrey, [127, 127, 127]),
+    (Color::Red, [255, 0, 0]),
+    (Color::Green, [0, 255, 0]),
+    (Color::Yellow, [255, 255, 0]),
+    (Color::Blue, [92, 92, 255]),
+    (Color::Magenta, [255, 0, 255]),
+    (Color::Cyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+/// Quantizes a row-major grid of colours `width` wide to `depth` in place,
+/// spreading the error as `dither` says. Colours other than RGB are left
+/// untouched and take no part in the dithering.
+pub fn quantize(colors: &mut [Color], width: usize, depth: ColorDepth, dither: Dither) {
+    if depth == ColorDepth::TrueColor {
+        return;
+    }
+
+    let mut errors = vec![[0f32; 3]; colors.len()];
+
+    for index in 0..colors.len() {
+        let Color::Rgb { r, g, b } = colors[index] else {
+            continue;
+        };
+        let (x, y) = (index % width, index / width);
+        let mut rgb = [r, g, b].map(f32::from);
+
+        match dither {
+            Dither::None => (),
+            Dither::Ordered => {
+                let spread = depth.step(depth.nearest(rgb).1);
+                let offset = (BAYER[y % 4][x % 4] as f32 + 0.5) / 16. - 0.5;
+                rgb = rgb.map(|channel| offset.mul_add(spread, channel));
+            }
+            Dither::FloydSteinberg => {
+                rgb = std::array::from_fn(|i| rgb[i] + errors[index][i]);
+            }
+        }
+
+        let (color, shown) = depth.nearest(rgb);
+        colors[index] = color;
+
+        if dither == Dither::FloydSteinberg {
+            let error: [f32; 3] = std::array::from_fn(|i| rgb[i].clamp(0., 255.) - shown[i]);
+            let mut spread = |dx: isize, dy: usize, weight: f32| {
+                let x = x as isize + dx;
+                if (0..width as isize).contains(&x) {
+                    if let Some(target) = errors.get_mut((y + dy) * width + x as usize) {
+                        for (target, error) in target.iter_mut().zip(error) {
+                            *target += error * weight / 16.;
+                        }
+                    }
+                }
+            };
+            spread(1, 0, 7.);
+            spread(-1, 1, 3.);
+            spread(0, 1, 5.);
+            spread(1, 1, 1.);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(value: u8) -> Color {
+        Color::Rgb {
+            r: value,
+            g: value,
+            b: value,
+        }
+    }
+
+    #[test]
+    fn detection() {
+        for (colorterm, term, depth) in [
+            (
+                Some("truecolor"),
+                Some("xterm-256color"),
+                ColorDepth::TrueColor,
+            ),
+            (Some("24bit"), None, ColorDepth::TrueColor),
+            (None, Some("xterm-direct"), ColorDepth::TrueColor),
+            (None, Some("screen-256color"), ColorDepth::Ansi256),
+            (Some("yes"), Some("xterm-256color"), ColorDepth::Ansi256),
+            (None, Some("xterm"), ColorDepth::Ansi16),
+            (None, Some("linux"), ColorDepth::Ansi16),
+            (None, None, ColorDepth::Ansi16),
+        ] {
+            assert_eq!(ColorDepth::from_env(colorterm, term), depth);
+        }
+    }
+
+    #[test]
+    fn ansi256() {
+        let depth = ColorDepth::Ansi256;
+        assert_eq!(depth.quantize(gray(0)), Color::AnsiValue(16));
+        assert_eq!(depth.quantize(gray(128)), Color::AnsiValue(244));
+        assert_eq!(depth.quantize(gray(255)), Color::AnsiValue(231));
+        assert_eq!(
+            depth.quantize(Color::Rgb { r: 250, g: 2, b: 0 }),
+            Color::AnsiValue(196)
+        );
+        assert_eq!(
+            depth.quantize(Color::Rgb {
+                r: 90,
+                g: 140,
+                b: 220
+            }),
+            Color::AnsiValue(16 + 36 + 6 * 2 + 4)
+        );
+    }
+
+    #[test]
+    fn ansi16() {
+        let depth = ColorDepth::Ansi16;
+        assert_eq!(depth.quantize(gray(10)), Color::Black);
+        assert_eq!(depth.quantize(gray(140)), Color::DarkGrey);
+        assert_eq!(depth.quantize(gray(240)), Color::Grey);
+        assert_eq!(
+            depth.quantize(Color::Rgb {
+                r: 190,
+                g: 20,
+                b: 10
+            }),
+            Color::DarkRed
+        );
+        assert_eq!(depth.quantize(Color::Reset), Color::Reset);
+    }
+
+    #[test]
+    fn true_color_untouched() {
+        let mut colors = vec![gray(77), Color::Rgb { r: 1, g: 2, b: 3 }];
+        let original = colors.clone();
+        quantize(
+            &mut colors,
+            2,
+            ColorDepth::TrueColor,
+            Dither::FloydSteinberg,
+        );
+        assert_eq!(colors, original);
+    }
+
+    /// Mean gray shown by a flat 8x8 patch of `value` quantized to 16 colours
+    /// and whether the patch mixes several colours.
+    fn shown_mean(value: u8, dither: Dither) -> (f32, bool) {
+        let mut colors = vec![gray(value); 64];
+        quantize(&mut colors, 8, ColorDepth::Ansi16, dither);
+
+        let shown = |color| ANSI16.iter().find(|(c, _)| *c == color).unwrap().1[0] as f32;
+        let mean = colors.iter().map(|&color| shown(color)).sum::<f32>() / 64.;
+        (mean, colors.iter().any(|&color| color != colors[0]))
+    }
+
+    #[test]
+    fn dithering_mixes_colours() {
+        assert_eq!(shown_mean(40, Dither::None), (0., false));
+
+        for dither in [Dither::Ordered, Dither::FloydSteinberg] {
+            let (mean, mixed) = shown_mean(40, dither);
+            assert!(mixed, "{dither:?}");
+            assert!((mean - 40.).abs() < 16., "{dither:?} mean {mean}");
+        }
+
+        // between ramp entries 246 at 148 and 247 at 158, away from cube grays
+        let mut colors = vec![gray(153); 64];
+        quantize(&mut colors, 8, ColorDepth::Ansi256, Dither::Ordered);
+
+        for color in [Color::AnsiValue(246), Color::AnsiValue(247)] {
+            assert!(colors.contains(&color), "{colors:?}");
+        }
+        assert!(
+            colors
+                .iter()
+                .all(|&color| color == Color::AnsiValue(246) || color == Color::AnsiValue(247)),
+            "{colors:?}"
+        );
+    }
+}